@@ -10,6 +10,73 @@ pub enum SortOrder {
     Modified,
     /// Sort by creation time
     Created,
+    /// Sort by search relevance, best matches first (alphabetical when there are no terms)
+    Relevance,
+}
+
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum GraphFormat {
+    /// Graphviz DOT (default)
+    #[default]
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+    /// GraphML (XML)
+    Graphml,
+    /// JSON node and edge lists
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum DryRunFormat {
+    /// Unified diff (default)
+    Diff,
+    /// JSON list of changes
+    Json,
+}
+
+#[derive(Subcommand)]
+pub enum GraphCommand {
+    /// Report orphans, dead ends, hubs, components and clusters
+    Stats {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+        /// Minimum number of linking topics for a hub (default: [lint] hub_threshold)
+        #[arg(long, value_name = "N")]
+        hub_threshold: Option<usize>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TemplatesCommand {
+    /// List templates with their descriptions and folders
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TrashCommand {
+    /// List deleted topics, most recent first
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Put a deleted topic back where it was
+    Restore {
+        /// The topic's original path or filename, or a number (`#N`) from `hypha trash list`
+        item: String,
+    },
+    /// Permanently delete everything in the trash
+    Empty {
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
 }
 
 #[derive(Parser)]
@@ -21,6 +88,18 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub root: Option<PathBuf>,
 
+    /// Show the changes a command would make without writing anything
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "diff"
+    )]
+    pub dry_run: Option<DryRunFormat>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -34,6 +113,60 @@ pub enum Commands {
         /// Don't open the file in editor after creation
         #[arg(short, long)]
         no_edit: bool,
+        /// Named template from .hypha/templates/ to use
+        #[arg(short, long, value_name = "NAME")]
+        template: Option<String>,
+        /// Value for a {prompt:NAME} placeholder (repeatable)
+        #[arg(long = "var", value_name = "NAME=VALUE")]
+        vars: Vec<String>,
+        /// Frontmatter field to set (repeatable)
+        #[arg(long = "field", value_name = "KEY=VALUE")]
+        fields: Vec<String>,
+        /// Directory for the topic, relative to the root
+        #[arg(long, value_name = "DIR")]
+        dir: Option<String>,
+        /// Text to add after the template, or - to read it from stdin
+        #[arg(long, value_name = "TEXT")]
+        body: Option<String>,
+        /// Print only the new topic's path (doesn't open the editor)
+        #[arg(long, conflicts_with = "json")]
+        print_path: bool,
+        /// Output as JSON (doesn't open the editor)
+        #[arg(long)]
+        json: bool,
+    },
+    /// Create or open today's journal note
+    Today {
+        /// Use the weekly note
+        #[arg(short, long, conflicts_with = "monthly")]
+        weekly: bool,
+        /// Use the monthly note
+        #[arg(short, long)]
+        monthly: bool,
+        /// Don't open the file in editor
+        #[arg(short, long)]
+        no_edit: bool,
+        /// Print only the note's path (doesn't open the editor)
+        #[arg(long)]
+        print_path: bool,
+    },
+    /// Create or open the journal note for a date
+    Journal {
+        /// today, yesterday, tomorrow, YYYY-MM-DD, YYYY-MM, or an offset like +1 or -2
+        #[arg(default_value = "today", allow_hyphen_values = true)]
+        date: String,
+        /// Use the weekly note
+        #[arg(short, long, conflicts_with = "monthly")]
+        weekly: bool,
+        /// Use the monthly note
+        #[arg(short, long)]
+        monthly: bool,
+        /// Don't open the file in editor
+        #[arg(short, long)]
+        no_edit: bool,
+        /// Print only the note's path (doesn't open the editor)
+        #[arg(long)]
+        print_path: bool,
     },
     /// List all topics
     List {
@@ -46,7 +179,7 @@ pub enum Commands {
     },
     /// Search topics by query
     Search {
-        /// Search query (supports filters like status:active priority:high)
+        /// Search query (supports filters, OR, -negation and grouping)
         query: String,
         /// Output as JSON
         #[arg(long)]
@@ -55,17 +188,39 @@ pub enum Commands {
         #[arg(long, short, value_enum, default_value = "alpha")]
         sort: SortOrder,
     },
+    /// List checklist tasks across topics
+    Tasks {
+        /// Filter tasks (status:open, due<2026-11-01, owner:sam, or any topic field)
+        #[arg(default_value = "", allow_hyphen_values = true)]
+        query: String,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// Open a topic in your default editor
     Open {
         /// Topic title or path
         topic: String,
     },
-    /// Delete a topic
+    /// Move a topic to the trash
     Delete {
         /// Topic title or path
         topic: String,
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
     },
-    /// Lint topics for issues (missing title, empty content)
+    /// List named templates for `hypha new --template`
+    Templates {
+        #[command(subcommand)]
+        command: TemplatesCommand,
+    },
+    /// List, restore or permanently delete topics in the trash
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommand,
+    },
+    /// Lint topics for issues (missing titles, frontmatter, broken links and anchors)
     Lint {
         /// Output as JSON
         #[arg(long)]
@@ -79,11 +234,25 @@ pub enum Commands {
     },
     /// Show topics that link to this topic
     Backlinks {
-        /// Topic title or filename
+        /// Topic title, filename or path relative to the root
         topic: String,
         /// Output as JSON
         #[arg(long)]
         json: bool,
+        /// Find mentions of the topic's title or aliases that aren't links
+        #[arg(long)]
+        unlinked: bool,
+        /// Turn unlinked mentions into links: all, or the numbered ones (--link=1,3)
+        #[arg(
+            long,
+            value_name = "N",
+            num_args = 0..=1,
+            require_equals = true,
+            value_delimiter = ',',
+            requires = "unlinked",
+            conflicts_with = "json"
+        )]
+        link: Option<Vec<usize>>,
     },
     /// Rename a topic and update all links
     Rename {
@@ -92,4 +261,63 @@ pub enum Commands {
         /// New title
         to: String,
     },
+    /// Move topics into a folder and update links to and from them
+    #[command(
+        override_usage = "hypha move <TOPIC> <DIR>\n       hypha move --where <QUERY> <DIR>"
+    )]
+    Move {
+        /// Topic title or path, then a directory relative to the root (only the directory with --where)
+        #[arg(value_name = "ARGS", required = true)]
+        args: Vec<String>,
+        /// Move every topic matching this search query
+        #[arg(long = "where", value_name = "QUERY")]
+        filter: Option<String>,
+    },
+    /// Export the link graph, or report on its structure
+    #[command(args_conflicts_with_subcommands = true)]
+    Graph {
+        #[command(subcommand)]
+        command: Option<GraphCommand>,
+        /// Output format
+        #[arg(long, short, value_enum, default_value = "dot")]
+        format: GraphFormat,
+        /// Only include topics matching this search query
+        #[arg(long = "where", value_name = "QUERY")]
+        filter: Option<String>,
+        /// Only include topics within --depth links of this topic
+        #[arg(long, value_name = "TOPIC")]
+        around: Option<String>,
+        /// Number of links to follow from --around, in either direction
+        #[arg(long, default_value_t = 1, requires = "around")]
+        depth: usize,
+        /// Frontmatter fields to include as node attributes (default: all)
+        #[arg(long, value_delimiter = ',', value_name = "FIELDS")]
+        fields: Vec<String>,
+    },
+    /// Revert the last change made by new, delete, rename, move, set, unset or backlinks --link
+    Undo,
+    /// Set frontmatter fields, keeping the rest of the file as written
+    #[command(
+        override_usage = "hypha set <TOPIC> <KEY=VALUE>...\n       hypha set --where <QUERY> <KEY=VALUE>..."
+    )]
+    Set {
+        /// Topic title or path, then key=value pairs (only the pairs with --where)
+        #[arg(value_name = "ARGS", required = true)]
+        args: Vec<String>,
+        /// Update every topic matching this search query
+        #[arg(long = "where", value_name = "QUERY")]
+        filter: Option<String>,
+    },
+    /// Remove frontmatter fields
+    #[command(
+        override_usage = "hypha unset <TOPIC> <KEY>...\n       hypha unset --where <QUERY> <KEY>..."
+    )]
+    Unset {
+        /// Topic title or path, then field names (only the names with --where)
+        #[arg(value_name = "ARGS", required = true)]
+        args: Vec<String>,
+        /// Update every topic matching this search query
+        #[arg(long = "where", value_name = "QUERY")]
+        filter: Option<String>,
+    },
 }
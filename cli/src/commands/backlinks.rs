@@ -1,71 +1,105 @@
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use serde::Serialize;
+use std::fs;
 use std::path::Path;
 
-use crate::cli::SortOrder;
+use crate::cli::{DryRunFormat, SortOrder};
+use crate::core::changes::ChangeSet;
+use crate::core::graph::LinkGraph;
+use crate::core::history;
 use crate::core::index;
+use crate::core::links;
+use crate::core::markdown::ParsedLink;
+use crate::core::mentions::{self, Mention};
+use crate::core::topic::Topic;
 
 #[derive(Serialize)]
 struct BacklinkJson {
     title: String,
     path: String,
+    /// Every link from this topic to the target, in document order.
+    links: Vec<LinkJson>,
 }
 
-/// Find topics that link to the given topic.
-pub fn run(root: &Path, topic: &str, json: bool) -> Result<()> {
-    let topics = index::list(root, SortOrder::Alpha)?;
+#[derive(Serialize)]
+struct LinkJson {
+    line: usize,
+    column: usize,
+    /// Link text as displayed.
+    text: String,
+    /// Plain text of the paragraph, heading or list item containing the link.
+    context: String,
+}
 
-    // Find the target topic
-    let target = topics.iter().find(|t| {
-        t.title.eq_ignore_ascii_case(topic)
-            || t.path
-                .file_stem()
-                .map(|s| s.to_string_lossy().eq_ignore_ascii_case(topic))
-                .unwrap_or(false)
-    });
-
-    let target = match target {
-        Some(t) => t,
-        None => bail!("Topic not found: {}", topic),
-    };
-
-    let target_filename = target
-        .path
-        .file_name()
-        .map(|s| s.to_string_lossy().to_string())
-        .unwrap_or_default();
-    let target_stem = target
-        .path
-        .file_stem()
-        .map(|s| s.to_string_lossy().to_string())
-        .unwrap_or_default();
-
-    // Find topics that link to this target
-    let mut backlinks: Vec<&crate::core::topic::Topic> = Vec::new();
+#[derive(Serialize)]
+struct MentionJson {
+    /// 1-based number, as accepted by `--link=N`.
+    number: usize,
+    title: String,
+    path: String,
+    line: usize,
+    column: usize,
+    text: String,
+    snippet: String,
+}
 
-    for t in &topics {
-        if t.path == target.path {
-            continue; // Skip self
+/// Find the topic backlinks are wanted for, by path relative to the root
+/// (with or without `.md`), title or file stem.
+fn find_target<'a>(root: &Path, topics: &'a [Topic], topic: &str) -> Result<&'a Topic> {
+    let path = links::normalize(&root.join(topic));
+    if let Some(t) = topics.iter().find(|t| {
+        let candidate = links::normalize(&t.path);
+        candidate == path || candidate.with_extension("") == path
+    }) {
+        return Ok(t);
+    }
+
+    let matches: Vec<&Topic> = topics
+        .iter()
+        .filter(|t| {
+            t.title.eq_ignore_ascii_case(topic)
+                || t.path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().eq_ignore_ascii_case(topic))
+                    .unwrap_or(false)
+        })
+        .collect();
+
+    match matches.as_slice() {
+        [] => bail!("Topic not found: {}", topic),
+        [t] => Ok(t),
+        _ => {
+            let paths: Vec<String> = matches
+                .iter()
+                .map(|t| format!("  {}", t.path.display()))
+                .collect();
+            bail!(
+                "Multiple topics match: {}\n{}\nUse a path to pick one",
+                topic,
+                paths.join("\n")
+            )
         }
+    }
+}
 
-        for link in &t.links {
-            // Extract the filename or stem from the link target
-            let link_path = std::path::Path::new(&link.target);
-            let link_filename = link_path
-                .file_name()
-                .map(|s| s.to_string_lossy().to_lowercase())
-                .unwrap_or_default();
-            let link_stem = link_path
-                .file_stem()
-                .map(|s| s.to_string_lossy().to_lowercase())
-                .unwrap_or_default();
-
-            // Exact match on filename or stem
-            if link_filename == target_filename.to_lowercase()
-                || link_stem == target_stem.to_lowercase()
-            {
-                backlinks.push(t);
-                break;
+/// Find topics that link to the given topic.
+///
+/// Links are resolved as `hypha lint` checks them: markdown links relative to
+/// the linking file, wiki links by path, stem or title.
+pub fn run(root: &Path, topic: &str, json: bool) -> Result<()> {
+    let topics = index::list(root, SortOrder::Alpha)?;
+    let target = find_target(root, &topics, topic)?;
+    let graph = LinkGraph::build(&topics);
+
+    // Every link from each other topic to the target, in topic order
+    let mut backlinks: Vec<(&Topic, Vec<&ParsedLink>)> = Vec::new();
+    if let Some(node) = graph.node(&target.path) {
+        for edge in graph.incoming(node) {
+            let source = &topics[edge.source];
+            let link = &source.links[edge.link];
+            match backlinks.last_mut() {
+                Some((t, links)) if t.path == source.path => links.push(link),
+                _ => backlinks.push((source, vec![link])),
             }
         }
     }
@@ -73,9 +107,18 @@ pub fn run(root: &Path, topic: &str, json: bool) -> Result<()> {
     if json {
         let output: Vec<BacklinkJson> = backlinks
             .iter()
-            .map(|t| BacklinkJson {
+            .map(|(t, links)| BacklinkJson {
                 title: t.title.clone(),
                 path: t.path.display().to_string(),
+                links: links
+                    .iter()
+                    .map(|link| LinkJson {
+                        line: link.line,
+                        column: link.column,
+                        text: link.text.clone(),
+                        context: link.context.clone(),
+                    })
+                    .collect(),
             })
             .collect();
         println!("{}", serde_json::to_string_pretty(&output)?);
@@ -83,12 +126,130 @@ pub fn run(root: &Path, topic: &str, json: bool) -> Result<()> {
         println!("No backlinks found for: {}", target.title);
     } else {
         println!("Topics linking to \"{}\":", target.title);
-        println!();
-        for t in &backlinks {
+        for (t, links) in &backlinks {
+            println!();
             println!("  {} ({})", t.title, t.path.display());
+            for link in links {
+                println!("    {}:{} {}", link.line, link.column, link.text);
+                if !link.context.is_empty() {
+                    println!("      {}", link.context);
+                }
+            }
+        }
+        println!();
+        let link_count: usize = backlinks.iter().map(|(_, links)| links.len()).sum();
+        println!(
+            "{} backlink(s) found ({} link(s))",
+            backlinks.len(),
+            link_count
+        );
+    }
+
+    Ok(())
+}
+
+/// Find mentions of a topic's title or aliases that aren't links.
+///
+/// With `link`, the numbered mentions (all of them if none are given) are
+/// turned into markdown links to the topic.
+pub fn unlinked(
+    root: &Path,
+    topic: &str,
+    json: bool,
+    link: Option<&[usize]>,
+    dry_run: Option<DryRunFormat>,
+) -> Result<()> {
+    let topics = index::list(root, SortOrder::Alpha)?;
+    let target = find_target(root, &topics, topic)?;
+
+    let mut terms = vec![target.title.clone()];
+    terms.extend(target.aliases());
+
+    // Read each note from disk so mention offsets match the file exactly
+    let mut found: Vec<(&Topic, String, Vec<Mention>)> = Vec::new();
+    for t in &topics {
+        if t.path == target.path {
+            continue;
+        }
+        let content = fs::read_to_string(&t.path)
+            .with_context(|| format!("Failed to read {}", t.path.display()))?;
+        let mentions = mentions::find(&content, &terms);
+        if !mentions.is_empty() {
+            found.push((t, content, mentions));
+        }
+    }
+    let total: usize = found.iter().map(|(_, _, m)| m.len()).sum();
+
+    if let Some(selected) = link {
+        if let Some(n) = selected.iter().find(|&&n| n == 0 || n > total) {
+            bail!("No unlinked mention {} (found {})", n, total);
+        }
+        let mut number = 0;
+        let mut changes = ChangeSet::new();
+        let mut linked = Vec::new();
+        for (t, content, file_mentions) in &found {
+            let chosen: Vec<&Mention> = file_mentions
+                .iter()
+                .filter(|_| {
+                    number += 1;
+                    selected.is_empty() || selected.contains(&number)
+                })
+                .collect();
+            if chosen.is_empty() {
+                continue;
+            }
+            let href = links::relative_href(&t.path, &target.path);
+            changes.write(&t.path, mentions::link(content, &chosen, &href))?;
+            linked.push((chosen.len(), &t.path));
+        }
+
+        if let Some(format) = dry_run {
+            return changes.print(root, format);
+        }
+        changes.commit(
+            root,
+            &history::describe(&["backlinks", "--unlinked", "--link", topic]),
+        )?;
+
+        for (count, path) in &linked {
+            println!("Linked {} mention(s) in: {}", count, path.display());
+        }
+        if linked.is_empty() {
+            println!("No unlinked mentions of: {}", target.title);
+        }
+        return Ok(());
+    }
+
+    let numbered = found
+        .iter()
+        .flat_map(|(t, _, file_mentions)| file_mentions.iter().map(move |m| (*t, m)))
+        .enumerate()
+        .map(|(i, (t, m))| (i + 1, t, m));
+
+    if json {
+        let output: Vec<MentionJson> = numbered
+            .map(|(number, t, m)| MentionJson {
+                number,
+                title: t.title.clone(),
+                path: t.path.display().to_string(),
+                line: m.line,
+                column: m.column,
+                text: m.text.clone(),
+                snippet: m.snippet.clone(),
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if total == 0 {
+        println!("No unlinked mentions of: {}", target.title);
+    } else {
+        println!("Unlinked mentions of \"{}\":", target.title);
+        println!();
+        for (number, t, m) in numbered {
+            println!("  {}. {}:{}:{}", number, t.path.display(), m.line, m.column);
+            println!("     {}", m.snippet);
         }
         println!();
-        println!("{} backlink(s) found", backlinks.len());
+        println!("{} unlinked mention(s) found", total);
     }
 
     Ok(())
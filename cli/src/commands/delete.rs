@@ -1,44 +1,61 @@
-use anyhow::{bail, Context, Result};
+use anyhow::{Context, Result};
 use std::fs;
 use std::path::Path;
 
-use crate::cli::SortOrder;
-use crate::core::index;
+use crate::cli::{DryRunFormat, SortOrder};
+use crate::core::changes::ChangeSet;
+use crate::core::links::LinkResolver;
+use crate::core::{history, index, trash};
+use crate::utils::confirm;
 
-/// Delete a topic.
-pub fn run(root: &Path, query: &str) -> Result<()> {
-    // First try exact path match
-    let direct_path = root.join(query);
-    if direct_path.exists() {
-        fs::remove_file(&direct_path).context("Failed to delete topic")?;
-        println!("Deleted: {}", direct_path.display());
-        return Ok(());
+/// Move a topic to the trash, after confirming unless `yes` is set.
+///
+/// Topics that still link to it are listed first, since their links will break.
+pub fn run(root: &Path, query: &str, yes: bool, dry_run: Option<DryRunFormat>) -> Result<()> {
+    let path = index::find_one(root, query)?;
+    let topics = index::list(root, SortOrder::Alpha)?;
+    let resolver = LinkResolver::new(&topics);
+
+    let title = match resolver.index_of(&path) {
+        Some(node) => {
+            let linking = resolver.linking_to(node);
+            if !linking.is_empty() {
+                eprintln!(
+                    "Warning: {} topic(s) link to {}:",
+                    linking.len(),
+                    topics[node].title
+                );
+                for source in linking {
+                    eprintln!("  {}", topics[source].path.display());
+                }
+            }
+            topics[node].title.clone()
+        }
+        None => path
+            .file_name()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    };
+
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut changes = ChangeSet::new();
+    changes.write(&trash::slot(root, &path), content)?;
+    changes.remove(&path)?;
+    if let Some(format) = dry_run {
+        return changes.print(root, format);
     }
 
-    // Try with .md extension
-    let with_ext = root.join(format!("{}.md", query));
-    if with_ext.exists() {
-        fs::remove_file(&with_ext).context("Failed to delete topic")?;
-        println!("Deleted: {}", with_ext.display());
+    if !yes && !confirm(&format!("Delete {} ({})?", title, path.display()))? {
+        println!("Cancelled.");
         return Ok(());
     }
 
-    // Search by title
-    let topics = index::search(root, query, SortOrder::Alpha)?;
-
-    match topics.len() {
-        0 => bail!("No topic found matching: {}", query),
-        1 => {
-            fs::remove_file(&topics[0].path).context("Failed to delete topic")?;
-            println!("Deleted: {}", topics[0].path.display());
-            Ok(())
-        }
-        _ => {
-            println!("Multiple matches found:");
-            for (i, topic) in topics.iter().enumerate() {
-                println!("  {}: {}", i + 1, topic.title);
-            }
-            bail!("Please be more specific");
-        }
-    }
+    changes.commit(root, &history::describe(&["delete", query]))?;
+    println!("Deleted: {}", path.display());
+    println!(
+        "  Restore with: hypha trash restore {}",
+        history::describe(&[&path.strip_prefix(root).unwrap_or(&path).to_string_lossy()])
+    );
+    Ok(())
 }
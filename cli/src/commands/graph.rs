@@ -0,0 +1,427 @@
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::cli::{GraphFormat, SortOrder};
+use crate::core::graph::LinkGraph;
+use crate::core::query::{self, Query};
+use crate::core::settings::Settings;
+use crate::core::topic::Topic;
+use crate::core::{index, links};
+
+/// A topic as exported in the graph.
+#[derive(Serialize)]
+struct NodeJson {
+    /// Path relative to the root, without `.md`.
+    id: String,
+    title: String,
+    path: String,
+    /// Frontmatter fields, as written.
+    fields: BTreeMap<String, serde_yaml::Value>,
+}
+
+/// Links from one topic to another.
+#[derive(Serialize)]
+struct EdgeJson {
+    source: String,
+    target: String,
+    /// Number of links between the pair.
+    links: usize,
+}
+
+#[derive(Serialize)]
+struct GraphJson {
+    nodes: Vec<NodeJson>,
+    edges: Vec<EdgeJson>,
+}
+
+/// A topic and how many topics link to it.
+#[derive(Serialize)]
+struct HubJson {
+    id: String,
+    linked_from: usize,
+}
+
+#[derive(Serialize)]
+struct StatsJson {
+    topics: usize,
+    links: usize,
+    orphans: Vec<String>,
+    dead_ends: Vec<String>,
+    hubs: Vec<HubJson>,
+    /// Connected components (ignoring link direction), largest first.
+    components: Vec<Vec<String>>,
+    /// Groups of topics that all reach each other, largest first.
+    clusters: Vec<Vec<String>>,
+}
+
+/// Which topics to include in an export.
+pub struct Selection<'a> {
+    /// Only topics matching this search query.
+    pub filter: Option<&'a str>,
+    /// Only topics within `depth` links of this topic.
+    pub around: Option<&'a str>,
+    pub depth: usize,
+    /// Frontmatter fields to export (all if empty).
+    pub fields: &'a [String],
+}
+
+/// Export the link graph, or part of it, in the given format.
+pub fn run(root: &Path, format: GraphFormat, selection: &Selection) -> Result<()> {
+    let topics = index::build(root, SortOrder::Alpha)?;
+    let graph = LinkGraph::build(&topics);
+
+    let mut nodes: BTreeSet<usize> = (0..topics.len()).collect();
+    if let Some(topic) = selection.around {
+        let path = index::find_one(root, topic)?;
+        let Some(start) = graph.node(&path) else {
+            bail!("Topic is not indexed: {}", path.display());
+        };
+        nodes = graph.neighbourhood(start, selection.depth);
+    }
+    if let Some(filter) = selection.filter {
+        let query = Query::parse(filter)?;
+        nodes.retain(|&i| query::matches(&topics[i], &query));
+    }
+
+    let export = GraphJson {
+        nodes: nodes
+            .iter()
+            .map(|&i| node_json(root, &topics[i], selection.fields))
+            .collect(),
+        edges: graph
+            .connections(&nodes)
+            .into_iter()
+            .map(|(source, target, links)| EdgeJson {
+                source: node_id(root, &topics[source]),
+                target: node_id(root, &topics[target]),
+                links,
+            })
+            .collect(),
+    };
+
+    let output = match format {
+        GraphFormat::Dot => to_dot(&export),
+        GraphFormat::Mermaid => to_mermaid(&export),
+        GraphFormat::Graphml => to_graphml(&export),
+        GraphFormat::Json => serde_json::to_string_pretty(&export)? + "\n",
+    };
+    print!("{}", output);
+    Ok(())
+}
+
+/// Report orphans, dead ends, hubs, components and clusters.
+pub fn stats(root: &Path, json: bool, hub_threshold: Option<usize>) -> Result<()> {
+    let hub_threshold = match hub_threshold {
+        Some(threshold) => threshold,
+        None => Settings::load(root)?.lint.hub_threshold,
+    };
+    let topics = index::build(root, SortOrder::Alpha)?;
+    let graph = LinkGraph::build(&topics);
+
+    let id = |node: usize| node_id(root, &topics[node]);
+    let ids = |nodes: Vec<usize>| nodes.into_iter().map(id).collect::<Vec<_>>();
+    let nodes = 0..topics.len();
+
+    let mut hubs: Vec<HubJson> = nodes
+        .clone()
+        .map(|node| (node, graph.linked_from(node)))
+        .filter(|&(_, linked_from)| linked_from > 0 && linked_from >= hub_threshold)
+        .map(|(node, linked_from)| HubJson {
+            id: id(node),
+            linked_from,
+        })
+        .collect();
+    hubs.sort_by_key(|hub| std::cmp::Reverse(hub.linked_from));
+
+    let report = StatsJson {
+        topics: topics.len(),
+        links: graph.edges.len(),
+        orphans: ids(nodes.clone().filter(|&n| graph.is_orphan(n)).collect()),
+        dead_ends: ids(nodes.filter(|&n| graph.is_dead_end(n)).collect()),
+        hubs,
+        components: graph.components().into_iter().map(ids).collect(),
+        clusters: graph.clusters().into_iter().map(ids).collect(),
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!("Topics:     {}", report.topics);
+    println!("Links:      {}", report.links);
+    println!("Components: {}", report.components.len());
+    println!("Clusters:   {}", report.clusters.len());
+
+    print_section(
+        &format!("Orphans ({})", report.orphans.len()),
+        &report.orphans,
+    );
+    print_section(
+        &format!("Dead ends ({})", report.dead_ends.len()),
+        &report.dead_ends,
+    );
+    let hubs: Vec<String> = report
+        .hubs
+        .iter()
+        .map(|hub| format!("{} ({})", hub.id, hub.linked_from))
+        .collect();
+    print_section(
+        &format!("Hubs (linked from {}+ topics)", hub_threshold),
+        &hubs,
+    );
+    let groups = |groups: &[Vec<String>]| {
+        groups
+            .iter()
+            .map(|group| format!("{} topic(s): {}", group.len(), group.join(", ")))
+            .collect::<Vec<_>>()
+    };
+    print_section("Components", &groups(&report.components));
+    print_section("Clusters", &groups(&report.clusters));
+    Ok(())
+}
+
+fn print_section(heading: &str, lines: &[String]) {
+    println!("\n{}:", heading);
+    if lines.is_empty() {
+        println!("  (none)");
+    }
+    for line in lines {
+        println!("  {}", line);
+    }
+}
+
+/// Stable, readable node id: the path relative to the root without `.md`.
+fn node_id(root: &Path, topic: &Topic) -> String {
+    let path = links::normalize(&topic.path);
+    let relative = path.strip_prefix(links::normalize(root)).unwrap_or(&path);
+    relative
+        .with_extension("")
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn node_json(root: &Path, topic: &Topic, fields: &[String]) -> NodeJson {
+    NodeJson {
+        id: node_id(root, topic),
+        title: topic.title.clone(),
+        path: topic.path.display().to_string(),
+        fields: topic
+            .metadata
+            .iter()
+            .filter(|(key, value)| !value.is_null() && (fields.is_empty() || fields.contains(key)))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+    }
+}
+
+/// Render a frontmatter value as a single attribute string.
+fn attribute(value: &serde_yaml::Value) -> String {
+    match value {
+        serde_yaml::Value::String(s) => s.clone(),
+        serde_yaml::Value::Number(n) => n.to_string(),
+        serde_yaml::Value::Bool(b) => b.to_string(),
+        serde_yaml::Value::Sequence(items) => {
+            items.iter().map(attribute).collect::<Vec<_>>().join(", ")
+        }
+        other => serde_yaml::to_string(other)
+            .unwrap_or_default()
+            .trim_end()
+            .to_string(),
+    }
+}
+
+/// Graphviz DOT. Frontmatter fields become node attributes.
+fn to_dot(graph: &GraphJson) -> String {
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+
+    let mut out = String::from("digraph hypha {\n");
+    for node in &graph.nodes {
+        // Fields first, so `label` and `path` always describe the topic
+        let mut attrs: Vec<String> = node
+            .fields
+            .iter()
+            .map(|(key, value)| format!("{}={}", quote(key), quote(&attribute(value))))
+            .collect();
+        attrs.push(format!("label={}", quote(&node.title)));
+        attrs.push(format!("path={}", quote(&node.path)));
+        out.push_str(&format!("  {} [{}];\n", quote(&node.id), attrs.join(", ")));
+    }
+    for edge in &graph.edges {
+        let weight = if edge.links > 1 {
+            format!(" [weight={}]", edge.links)
+        } else {
+            String::new()
+        };
+        out.push_str(&format!(
+            "  {} -> {}{};\n",
+            quote(&edge.source),
+            quote(&edge.target),
+            weight
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Mermaid flowchart. Mermaid has no node attributes, so only titles are shown.
+fn to_mermaid(graph: &GraphJson) -> String {
+    // Mermaid ids must be simple identifiers, so number the nodes
+    let ids: BTreeMap<&str, String> = graph
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (node.id.as_str(), format!("n{}", i)))
+        .collect();
+
+    let mut out = String::from("graph LR\n");
+    for node in &graph.nodes {
+        let label = node.title.replace('"', "#quot;");
+        out.push_str(&format!("  {}[\"{}\"]\n", ids[node.id.as_str()], label));
+    }
+    for edge in &graph.edges {
+        out.push_str(&format!(
+            "  {} --> {}\n",
+            ids[edge.source.as_str()],
+            ids[edge.target.as_str()]
+        ));
+    }
+    out
+}
+
+/// GraphML, with a string key for every exported frontmatter field.
+fn to_graphml(graph: &GraphJson) -> String {
+    let escape = |s: &str| {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&apos;")
+    };
+    let field_names: BTreeSet<&str> = graph
+        .nodes
+        .iter()
+        .flat_map(|n| n.fields.keys().map(String::as_str))
+        .collect();
+
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n\
+         \x20 <key id=\"title\" for=\"node\" attr.name=\"title\" attr.type=\"string\"/>\n\
+         \x20 <key id=\"path\" for=\"node\" attr.name=\"path\" attr.type=\"string\"/>\n",
+    );
+    for name in &field_names {
+        out.push_str(&format!(
+            "  <key id=\"field:{0}\" for=\"node\" attr.name=\"{0}\" attr.type=\"string\"/>\n",
+            escape(name)
+        ));
+    }
+    out.push_str("  <key id=\"links\" for=\"edge\" attr.name=\"links\" attr.type=\"int\"/>\n");
+    out.push_str("  <graph id=\"hypha\" edgedefault=\"directed\">\n");
+
+    for node in &graph.nodes {
+        out.push_str(&format!("    <node id=\"{}\">\n", escape(&node.id)));
+        out.push_str(&format!(
+            "      <data key=\"title\">{}</data>\n",
+            escape(&node.title)
+        ));
+        out.push_str(&format!(
+            "      <data key=\"path\">{}</data>\n",
+            escape(&node.path)
+        ));
+        for (key, value) in &node.fields {
+            out.push_str(&format!(
+                "      <data key=\"field:{}\">{}</data>\n",
+                escape(key),
+                escape(&attribute(value))
+            ));
+        }
+        out.push_str("    </node>\n");
+    }
+    for edge in &graph.edges {
+        out.push_str(&format!(
+            "    <edge source=\"{}\" target=\"{}\">\n      <data key=\"links\">{}</data>\n    </edge>\n",
+            escape(&edge.source),
+            escape(&edge.target),
+            edge.links
+        ));
+    }
+
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> GraphJson {
+        GraphJson {
+            nodes: vec![
+                NodeJson {
+                    id: "projects/plan".to_string(),
+                    title: "The \"Plan\"".to_string(),
+                    path: "/notes/projects/plan.md".to_string(),
+                    fields: BTreeMap::from([
+                        (
+                            "status".to_string(),
+                            serde_yaml::Value::String("active".to_string()),
+                        ),
+                        ("tags".to_string(), serde_yaml::from_str("[a, b]").unwrap()),
+                    ]),
+                },
+                NodeJson {
+                    id: "ideas".to_string(),
+                    title: "Ideas & <Notes>".to_string(),
+                    path: "/notes/ideas.md".to_string(),
+                    fields: BTreeMap::new(),
+                },
+            ],
+            edges: vec![EdgeJson {
+                source: "projects/plan".to_string(),
+                target: "ideas".to_string(),
+                links: 2,
+            }],
+        }
+    }
+
+    #[test]
+    fn dot_output() {
+        assert_eq!(
+            to_dot(&sample()),
+            "digraph hypha {\n  \
+             \"projects/plan\" [\"status\"=\"active\", \"tags\"=\"a, b\", label=\"The \\\"Plan\\\"\", path=\"/notes/projects/plan.md\"];\n  \
+             \"ideas\" [label=\"Ideas & <Notes>\", path=\"/notes/ideas.md\"];\n  \
+             \"projects/plan\" -> \"ideas\" [weight=2];\n}\n"
+        );
+    }
+
+    #[test]
+    fn mermaid_output() {
+        assert_eq!(
+            to_mermaid(&sample()),
+            "graph LR\n  n0[\"The #quot;Plan#quot;\"]\n  n1[\"Ideas & <Notes>\"]\n  n0 --> n1\n"
+        );
+    }
+
+    #[test]
+    fn graphml_output_escapes_text() {
+        let xml = to_graphml(&sample());
+        assert!(xml.contains("<key id=\"field:status\" for=\"node\" attr.name=\"status\""));
+        assert!(xml.contains("<data key=\"title\">Ideas &amp; &lt;Notes&gt;</data>"));
+        assert!(xml.contains("<data key=\"field:tags\">a, b</data>"));
+        assert!(xml.contains("<edge source=\"projects/plan\" target=\"ideas\">"));
+        assert!(xml.ends_with("</graphml>\n"));
+    }
+
+    #[test]
+    fn node_ids_are_relative() {
+        let topic = Topic::from_content(Path::new("/notes/projects/plan.md"), "# Plan");
+        assert_eq!(node_id(Path::new("/notes"), &topic), "projects/plan");
+        assert_eq!(node_id(Path::new("/notes/"), &topic), "projects/plan");
+    }
+}
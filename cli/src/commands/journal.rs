@@ -0,0 +1,296 @@
+use anyhow::{bail, Context, Result};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{Datelike, Duration, Local, Months, NaiveDate, TimeZone};
+use std::path::Path;
+
+use crate::cli::DryRunFormat;
+use crate::constants::DEFAULT_JOURNAL_TEMPLATE;
+use crate::core::changes::ChangeSet;
+use crate::core::frontmatter;
+use crate::core::history::describe;
+use crate::core::settings::{PeriodSettings, Settings};
+use crate::core::template::{self, Template, Values};
+use crate::utils::{is_contained, read_stdin};
+
+/// How much time a journal note covers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Period {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl Period {
+    /// Name of the period's template in `.hypha/templates/`, used when
+    /// `[journal.<period>]` doesn't name one.
+    fn name(self) -> &'static str {
+        match self {
+            Period::Daily => "daily",
+            Period::Weekly => "weekly",
+            Period::Monthly => "monthly",
+        }
+    }
+
+    fn default_filename(self) -> &'static str {
+        match self {
+            Period::Daily => "%Y-%m-%d",
+            Period::Weekly => "%G-W%V",
+            Period::Monthly => "%Y-%m",
+        }
+    }
+
+    fn default_title(self) -> &'static str {
+        match self {
+            Period::Daily => "%A, %-d %B %Y",
+            Period::Weekly => "Week %V, %G",
+            Period::Monthly => "%B %Y",
+        }
+    }
+
+    fn settings(self, settings: &Settings) -> &PeriodSettings {
+        match self {
+            Period::Daily => &settings.journal.daily,
+            Period::Weekly => &settings.journal.weekly,
+            Period::Monthly => &settings.journal.monthly,
+        }
+    }
+
+    /// The first day of the period containing `date`.
+    fn start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Daily => date,
+            Period::Weekly => date - Duration::days(date.weekday().num_days_from_monday().into()),
+            Period::Monthly => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// Move `date` by `n` periods.
+    fn offset(self, date: NaiveDate, n: i64) -> Option<NaiveDate> {
+        match self {
+            Period::Daily => date.checked_add_signed(Duration::try_days(n)?),
+            Period::Weekly => date.checked_add_signed(Duration::try_weeks(n)?),
+            Period::Monthly => {
+                let months = Months::new(u32::try_from(n.unsigned_abs()).ok()?);
+                if n < 0 {
+                    date.checked_sub_months(months)
+                } else {
+                    date.checked_add_months(months)
+                }
+            }
+        }
+    }
+}
+
+/// Open the journal note for `date`, creating it from the period's template
+/// if it doesn't exist yet.
+///
+/// `date` is `today`, `yesterday`, `tomorrow`, an ISO date, or an offset in
+/// periods like `+1` or `-2`. The note's `date` field is set to the first
+/// day of its period, so journal notes can be searched by date.
+pub fn run(
+    root: &Path,
+    date: &str,
+    period: Period,
+    open: bool,
+    print_path: bool,
+    dry_run: Option<DryRunFormat>,
+) -> Result<()> {
+    let settings = Settings::load(root)?;
+    let options = period.settings(&settings);
+    let date = resolve(date, period, Local::now().date_naive())?;
+
+    let filename = format_date(
+        date,
+        options
+            .filename
+            .as_deref()
+            .unwrap_or(period.default_filename()),
+    )?;
+    let relative = Path::new(&settings.journal.folder).join(format!("{}.md", filename));
+    if !is_contained(&relative) {
+        bail!(
+            "Journal notes must be inside the root: {}",
+            relative.display()
+        );
+    }
+    let path = root.join(&relative);
+
+    let mut changes = ChangeSet::new();
+    if !path.exists() {
+        let title = format_date(
+            date,
+            options.title.as_deref().unwrap_or(period.default_title()),
+        )?;
+        let content = render(root, period, options, date, &title)?;
+        changes.write(&path, content)?;
+    }
+    if let Some(format) = dry_run {
+        return changes.print(root, format);
+    }
+
+    let created = !changes.is_empty();
+    if created {
+        let date = date.to_string();
+        let command = match period {
+            Period::Daily => describe(&["journal", &date]),
+            Period::Weekly => describe(&["journal", "--weekly", &date]),
+            Period::Monthly => describe(&["journal", "--monthly", &date]),
+        };
+        changes.commit(root, &command)?;
+    }
+
+    if print_path {
+        println!("{}", path.display());
+    } else if created {
+        println!("Created: {}", path.display());
+    } else {
+        println!("Found: {}", path.display());
+    }
+
+    if open {
+        crate::commands::open::open_in_editor(&path)?;
+    }
+    Ok(())
+}
+
+/// Render a new journal note, with `{date}` and `{time}` placeholders taken
+/// from the note's date.
+fn render(
+    root: &Path,
+    period: Period,
+    options: &PeriodSettings,
+    date: NaiveDate,
+    title: &str,
+) -> Result<String> {
+    let text = match &options.template {
+        Some(name) => template::load_named(root, name)?,
+        None if template::dir(root)
+            .join(format!("{}.md", period.name()))
+            .exists() =>
+        {
+            template::load_named(root, period.name())?
+        }
+        None => DEFAULT_JOURNAL_TEMPLATE.to_string(),
+    };
+
+    let tmpl = Template::parse(&text)?;
+    let mut values = Values::gather(&tmpl, title, &[])?;
+    if let Some(now) = Local
+        .from_local_datetime(&date.and_time(values.now.time()))
+        .earliest()
+    {
+        values.now = now;
+    }
+    if tmpl.reads_stdin() {
+        values.stdin = Some(read_stdin("The template's {stdin}")?);
+    }
+
+    let content = tmpl.render(&values)?;
+    frontmatter::set_field(&content, "date", &date.to_string())
+}
+
+/// Turn a date argument into the first day of its period.
+fn resolve(spec: &str, period: Period, today: NaiveDate) -> Result<NaiveDate> {
+    let date = match spec.to_lowercase().as_str() {
+        "today" => Some(today),
+        "yesterday" => today.pred_opt(),
+        "tomorrow" => today.succ_opt(),
+        offset if offset.starts_with(['+', '-']) => {
+            let n: i64 = offset
+                .parse()
+                .with_context(|| format!("Invalid offset: {}", spec))?;
+            Some(
+                period
+                    .offset(today, n)
+                    .with_context(|| format!("Date out of range: {}", spec))?,
+            )
+        }
+        _ => NaiveDate::parse_from_str(spec, "%Y-%m-%d")
+            .or_else(|_| NaiveDate::parse_from_str(&format!("{}-01", spec), "%Y-%m-%d"))
+            .ok(),
+    };
+    match date {
+        Some(date) => Ok(period.start(date)),
+        None => bail!(
+            "Expected today, yesterday, tomorrow, a date (YYYY-MM-DD or YYYY-MM) or an offset like +1, got: {}",
+            spec
+        ),
+    }
+}
+
+/// Format `date` with a strftime pattern from the settings.
+fn format_date(date: NaiveDate, pattern: &str) -> Result<String> {
+    if pattern.is_empty() || StrftimeItems::new(pattern).any(|item| item == Item::Error) {
+        bail!("Invalid date format in [journal] settings: {}", pattern);
+    }
+    Ok(date.format(pattern).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn resolves_dates_to_period_start() {
+        // A Wednesday
+        let today = date("2026-03-18");
+
+        assert_eq!(resolve("today", Period::Daily, today).unwrap(), today);
+        assert_eq!(
+            resolve("yesterday", Period::Daily, today).unwrap(),
+            date("2026-03-17")
+        );
+        assert_eq!(
+            resolve("+1", Period::Daily, today).unwrap(),
+            date("2026-03-19")
+        );
+        assert_eq!(
+            resolve("2026-01-01", Period::Daily, today).unwrap(),
+            date("2026-01-01")
+        );
+
+        assert_eq!(
+            resolve("today", Period::Weekly, today).unwrap(),
+            date("2026-03-16")
+        );
+        assert_eq!(
+            resolve("-1", Period::Weekly, today).unwrap(),
+            date("2026-03-09")
+        );
+
+        assert_eq!(
+            resolve("today", Period::Monthly, today).unwrap(),
+            date("2026-03-01")
+        );
+        assert_eq!(
+            resolve("+10", Period::Monthly, today).unwrap(),
+            date("2027-01-01")
+        );
+        assert_eq!(
+            resolve("2025-12", Period::Monthly, today).unwrap(),
+            date("2025-12-01")
+        );
+
+        assert!(resolve("someday", Period::Daily, today).is_err());
+        assert!(resolve("+x", Period::Daily, today).is_err());
+    }
+
+    #[test]
+    fn formats_with_default_patterns() {
+        let monday = date("2026-03-16");
+        let format = |period: Period| format_date(monday, period.default_filename()).unwrap();
+
+        assert_eq!(format(Period::Daily), "2026-03-16");
+        assert_eq!(format(Period::Weekly), "2026-W12");
+        assert_eq!(format(Period::Monthly), "2026-03");
+        assert_eq!(
+            format_date(monday, Period::Daily.default_title()).unwrap(),
+            "Monday, 16 March 2026"
+        );
+        assert!(format_date(monday, "%Q").is_err());
+    }
+}
@@ -4,14 +4,28 @@ use std::collections::HashMap;
 use std::path::Path;
 
 use crate::cli::SortOrder;
+use crate::core::graph::LinkGraph;
 use crate::core::index;
-use crate::core::markdown::ParsedLink;
+use crate::core::links::{self, LinkResolver};
+use crate::core::markdown::{self, Heading, LinkKind, ParsedLink};
+use crate::core::schema::Schema;
+use crate::core::settings::{LintSettings, Settings};
 use crate::core::topic::{Topic, TopicWarning};
 
+/// How serious a warning is, so editors can show it appropriately.
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Severity {
+    /// Part of the topic couldn't be read (e.g. its frontmatter is ignored).
+    Error,
+    Warning,
+}
+
 /// A structured warning with message and optional position.
 #[derive(Serialize)]
 struct WarningInfo {
     message: String,
+    severity: Severity,
     /// 1-based line number (if applicable).
     #[serde(skip_serializing_if = "Option::is_none")]
     line: Option<usize>,
@@ -22,26 +36,86 @@ struct WarningInfo {
 
 impl From<&TopicWarning> for WarningInfo {
     fn from(warning: &TopicWarning) -> Self {
-        match warning {
-            TopicWarning::MissingTitle => WarningInfo {
-                message: "Missing title (no # heading)".to_string(),
-                line: None,
-                column: None,
-            },
-            TopicWarning::EmptyContent => WarningInfo {
-                message: "Empty content".to_string(),
-                line: None,
-                column: None,
-            },
+        let (message, position) = match warning {
+            TopicWarning::MissingTitle => ("Missing title (no # heading)".to_string(), None),
+            TopicWarning::EmptyContent => ("Empty content".to_string(), None),
             TopicWarning::BrokenLink {
                 target,
                 line,
                 column,
-            } => WarningInfo {
-                message: format!("Broken link: {}", target),
-                line: Some(*line),
-                column: Some(*column),
-            },
+            } => (format!("Broken link: {}", target), Some((*line, *column))),
+            TopicWarning::BrokenAnchor {
+                target,
+                line,
+                column,
+            } => (format!("Broken anchor: {}", target), Some((*line, *column))),
+            TopicWarning::AmbiguousLink {
+                target,
+                candidates,
+                line,
+                column,
+            } => (
+                format!(
+                    "Ambiguous link: {} (matches {})",
+                    target,
+                    candidates.join(", ")
+                ),
+                Some((*line, *column)),
+            ),
+            TopicWarning::InvalidFrontmatter {
+                message,
+                line,
+                column,
+            } => (
+                format!("Invalid frontmatter: {}", message),
+                Some((*line, *column)),
+            ),
+            TopicWarning::MissingField {
+                field,
+                line,
+                column,
+            } => (
+                format!("Missing required field: {}", field),
+                Some((*line, *column)),
+            ),
+            TopicWarning::InvalidFieldType {
+                field,
+                expected,
+                line,
+                column,
+            } => (
+                format!("Invalid type for {}: expected {}", field, expected),
+                Some((*line, *column)),
+            ),
+            TopicWarning::InvalidFieldValue {
+                field,
+                value,
+                allowed,
+                line,
+                column,
+            } => (
+                format!(
+                    "Invalid value for {}: {} (allowed: {})",
+                    field,
+                    value,
+                    allowed.join(", ")
+                ),
+                Some((*line, *column)),
+            ),
+            TopicWarning::Orphan | TopicWarning::DeadEnd | TopicWarning::Hub { .. } => {
+                (warning.to_string(), None)
+            }
+        };
+        let severity = match warning {
+            TopicWarning::InvalidFrontmatter { .. } => Severity::Error,
+            _ => Severity::Warning,
+        };
+
+        WarningInfo {
+            message,
+            severity,
+            line: position.map(|(line, _)| line),
+            column: position.map(|(_, column)| column),
         }
     }
 }
@@ -85,40 +159,182 @@ fn extract_link_path(target: &str) -> Option<&str> {
     Some(link_path)
 }
 
-/// Check if a link target exists relative to the topic's directory.
-fn check_link(topic_path: &Path, link: &ParsedLink) -> Option<TopicWarning> {
-    let link_path = extract_link_path(&link.target)?;
+/// Check if a link target, and any heading anchor it names, exists.
+///
+/// Markdown links are resolved relative to the topic's directory; wiki links
+/// are resolved by stem, title or partial path across all topics. Anchors are
+/// percent-decoded and lowercased, then compared against the target's heading
+/// slugs; `#^block` references are not checked.
+fn check_link(resolver: &LinkResolver, topic: &Topic, link: &ParsedLink) -> Option<TopicWarning> {
+    let broken_link = || TopicWarning::BrokenLink {
+        target: link.target.clone(),
+        line: link.line,
+        column: link.column,
+    };
+    let broken_anchor = || TopicWarning::BrokenAnchor {
+        target: link.target.clone(),
+        line: link.line,
+        column: link.column,
+    };
+    let anchor = link
+        .anchor()
+        .filter(|a| !a.is_empty() && !a.starts_with('^'));
+
+    if link.kind == LinkKind::Wiki {
+        let target = if link.path().is_empty() {
+            topic
+        } else {
+            match resolver.resolve_wiki(link.path()) {
+                Some(target) => target,
+                None => return Some(broken_link()),
+            }
+        };
+        // Wiki anchors name the heading text, e.g. [[Topic#Getting Started]]
+        return match anchor {
+            Some(anchor) if !has_anchor(&target.headings, &markdown::anchor_slug(anchor)) => {
+                Some(broken_anchor())
+            }
+            _ => None,
+        };
+    }
 
-    // Get the directory containing the topic
-    let topic_dir = topic_path.parent()?;
+    let Some(link_path) = extract_link_path(&link.target) else {
+        return match anchor {
+            Some(anchor) if !has_anchor(&topic.headings, anchor) => Some(broken_anchor()),
+            _ => None,
+        };
+    };
 
     // Resolve the link relative to the topic's directory
-    let target = topic_dir.join(link_path);
-
-    // Check if target exists
+    let target = links::normalize(&topic.path.parent()?.join(link_path));
     if !target.exists() {
-        Some(TopicWarning::BrokenLink {
-            target: link.target.clone(),
-            line: link.line,
-            column: link.column,
-        })
-    } else {
+        return Some(broken_link());
+    }
+
+    let anchor = anchor.filter(|_| target.extension().is_some_and(|ext| ext == "md"))?;
+    let found = match resolver.topic_at(&target) {
+        Some(t) => has_anchor(&t.headings, anchor),
+        // Not indexed (e.g. ignored), so read its headings from disk
+        None => Topic::from_path(&target)
+            .map(|t| has_anchor(&t.headings, anchor))
+            .unwrap_or(true),
+    };
+    if found {
         None
+    } else {
+        Some(broken_anchor())
+    }
+}
+
+/// Report a wiki link whose bare target matches several topics by stem.
+fn check_ambiguous(
+    resolver: &LinkResolver,
+    topic: &Topic,
+    link: &ParsedLink,
+) -> Option<TopicWarning> {
+    if link.kind != LinkKind::Wiki {
+        return None;
+    }
+    let matches = resolver.stem_matches(link.path());
+    if matches.len() < 2 {
+        return None;
+    }
+    Some(TopicWarning::AmbiguousLink {
+        target: link.target.clone(),
+        candidates: matches
+            .iter()
+            .map(|t| {
+                let href = links::relative_href(&topic.path, &t.path);
+                href.strip_suffix(".md").unwrap_or(&href).to_string()
+            })
+            .collect(),
+        line: link.line,
+        column: link.column,
+    })
+}
+
+/// Check whether any heading has the given anchor slug.
+fn has_anchor(headings: &[Heading], anchor: &str) -> bool {
+    let anchor = percent_decode(anchor).to_lowercase();
+    headings.iter().any(|h| h.anchor == anchor)
+}
+
+/// Decode `%XX` escapes in a link fragment, leaving malformed ones as is.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Apply the opt-in graph rules, returning warnings for each topic.
+fn check_graph(topics: &[Topic], rules: &LintSettings) -> Vec<Vec<TopicWarning>> {
+    if !rules.orphans && !rules.dead_ends && !rules.hubs {
+        return vec![Vec::new(); topics.len()];
     }
+
+    let graph = LinkGraph::build(topics);
+    (0..topics.len())
+        .map(|node| {
+            let mut warnings = Vec::new();
+            if rules.orphans && graph.is_orphan(node) {
+                warnings.push(TopicWarning::Orphan);
+            }
+            if rules.dead_ends && graph.is_dead_end(node) {
+                warnings.push(TopicWarning::DeadEnd);
+            }
+            let linked_from = graph.linked_from(node);
+            if rules.hubs && linked_from >= rules.hub_threshold {
+                warnings.push(TopicWarning::Hub { linked_from });
+            }
+            warnings
+        })
+        .collect()
 }
 
-/// Lint all topics for issues (missing title, empty content, broken links).
+/// Lint all topics for issues (missing title, empty content, schema violations,
+/// broken or ambiguous links and anchors, plus any graph rules enabled in settings).
 /// Returns only topics with warnings.
 pub fn run(root: &Path, json: bool) -> Result<()> {
+    let schema = Schema::load(root)?;
+    let settings = Settings::load(root)?;
     let mut topics = index::build(root, SortOrder::Alpha)?;
-
-    // Check for broken links in each topic
-    for topic in &mut topics {
-        for link in &topic.links.clone() {
-            if let Some(warning) = check_link(&topic.path, link) {
-                topic.warnings.push(warning);
+    let resolver = LinkResolver::new(&topics);
+
+    // Check frontmatter against the schema and links in each topic
+    let extra_warnings: Vec<Vec<TopicWarning>> = topics
+        .iter()
+        .map(|topic| {
+            let mut warnings = schema.validate(topic, &resolver);
+            for link in &topic.links {
+                warnings.extend(check_link(&resolver, topic, link));
+                warnings.extend(check_ambiguous(&resolver, topic, link));
             }
-        }
+            warnings
+        })
+        .collect();
+    let graph_warnings = check_graph(&topics, &settings.lint);
+    for ((topic, warnings), graph_warnings) in
+        topics.iter_mut().zip(extra_warnings).zip(graph_warnings)
+    {
+        topic.warnings.extend(warnings);
+        topic.warnings.extend(graph_warnings);
     }
 
     // Filter to only topics with warnings
@@ -155,6 +371,28 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    fn check(topics: &[Topic], topic: &Topic, link: &ParsedLink) -> Option<TopicWarning> {
+        check_link(&LinkResolver::new(topics), topic, link)
+    }
+
+    fn markdown_link(target: &str, line: usize, column: usize) -> ParsedLink {
+        ParsedLink {
+            target: target.to_string(),
+            kind: LinkKind::Markdown,
+            alias: None,
+            text: String::new(),
+            context: String::new(),
+            line,
+            column,
+        }
+    }
+
+    /// Write a topic to disk and parse it.
+    fn write_topic(path: &Path, content: &str) -> Topic {
+        std::fs::write(path, content).unwrap();
+        Topic::from_path(path).unwrap()
+    }
+
     #[test]
     fn extract_link_path_basic() {
         assert_eq!(extract_link_path("file.md"), Some("file.md"));
@@ -184,34 +422,22 @@ mod tests {
     #[test]
     fn check_link_existing_file() {
         let temp = TempDir::new().unwrap();
-        let topic_path = temp.path().join("topic.md");
-        let target_path = temp.path().join("target.md");
-
-        std::fs::write(&topic_path, "# Topic").unwrap();
-        std::fs::write(&target_path, "# Target").unwrap();
+        let topic = write_topic(&temp.path().join("topic.md"), "# Topic");
+        std::fs::write(temp.path().join("target.md"), "# Target").unwrap();
 
-        let link = ParsedLink {
-            target: "target.md".to_string(),
-            line: 1,
-            column: 1,
-        };
+        let link = markdown_link("target.md", 1, 1);
 
-        assert!(check_link(&topic_path, &link).is_none());
+        assert!(check(&[], &topic, &link).is_none());
     }
 
     #[test]
     fn check_link_missing_file() {
         let temp = TempDir::new().unwrap();
-        let topic_path = temp.path().join("topic.md");
-        std::fs::write(&topic_path, "# Topic").unwrap();
+        let topic = write_topic(&temp.path().join("topic.md"), "# Topic");
 
-        let link = ParsedLink {
-            target: "missing.md".to_string(),
-            line: 5,
-            column: 3,
-        };
+        let link = markdown_link("missing.md", 5, 3);
 
-        let result = check_link(&topic_path, &link);
+        let result = check(&[], &topic, &link);
         assert!(result.is_some());
 
         if let Some(TopicWarning::BrokenLink {
@@ -229,36 +455,167 @@ mod tests {
     }
 
     #[test]
-    fn check_link_skips_anchors() {
+    fn check_link_in_document_anchors() {
         let temp = TempDir::new().unwrap();
-        let topic_path = temp.path().join("topic.md");
-        std::fs::write(&topic_path, "# Topic").unwrap();
+        let topic = write_topic(&temp.path().join("topic.md"), "# Topic\n\n## Section");
 
-        let link = ParsedLink {
-            target: "#section".to_string(),
-            line: 1,
-            column: 1,
-        };
+        let link = markdown_link("#section", 1, 1);
+        assert!(check(&[], &topic, &link).is_none());
 
-        assert!(check_link(&topic_path, &link).is_none());
+        let link = markdown_link("#missing", 3, 2);
+        assert_eq!(
+            check(&[], &topic, &link),
+            Some(TopicWarning::BrokenAnchor {
+                target: "#missing".to_string(),
+                line: 3,
+                column: 2,
+            })
+        );
     }
 
     #[test]
     fn check_link_with_anchor_to_existing_file() {
         let temp = TempDir::new().unwrap();
-        let topic_path = temp.path().join("topic.md");
-        let target_path = temp.path().join("target.md");
+        let topic = write_topic(&temp.path().join("topic.md"), "# Topic");
+        std::fs::write(temp.path().join("target.md"), "# Target").unwrap();
+
+        // File exists but has no such heading
+        let link = markdown_link("target.md#section", 1, 1);
+        assert!(matches!(
+            check(&[], &topic, &link),
+            Some(TopicWarning::BrokenAnchor { .. })
+        ));
+    }
 
-        std::fs::write(&topic_path, "# Topic").unwrap();
-        std::fs::write(&target_path, "# Target").unwrap();
+    #[test]
+    fn check_link_with_valid_anchor_to_indexed_file() {
+        let temp = TempDir::new().unwrap();
+        let topic = write_topic(&temp.path().join("topic.md"), "# Topic");
+        let target = write_topic(
+            &temp.path().join("target.md"),
+            "# Target\n\n## Getting Started\n\n## Café",
+        );
+        let topics = vec![topic.clone(), target];
+
+        for target in [
+            "target.md#getting-started",
+            "./target.md#target",
+            "target.md#Getting-Started",
+            "target.md#caf%C3%A9",
+        ] {
+            let link = markdown_link(target, 1, 1);
+            assert!(check(&topics, &topic, &link).is_none(), "{}", target);
+        }
+    }
+
+    #[test]
+    fn decodes_percent_escapes() {
+        assert_eq!(percent_decode("caf%C3%A9"), "café");
+        assert_eq!(percent_decode("a%20b"), "a b");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+    }
+
+    #[test]
+    fn check_link_skips_block_references() {
+        let temp = TempDir::new().unwrap();
+        let topic = write_topic(&temp.path().join("topic.md"), "# Topic");
+
+        let link = markdown_link("#^block-id", 1, 1);
+        assert!(check(&[], &topic, &link).is_none());
+    }
+
+    #[test]
+    fn check_wiki_link_resolves_by_stem_or_title() {
+        let topics = vec![Topic::from_content(
+            Path::new("/notes/target.md"),
+            "# Target Topic\n\n## Heading",
+        )];
+        let topic = Topic::from_content(Path::new("/notes/topic.md"), "# Topic");
+
+        for target in ["target", "Target Topic", "target#Heading", "target#^block"] {
+            let link = ParsedLink {
+                kind: LinkKind::Wiki,
+                ..markdown_link(target, 1, 1)
+            };
+            assert!(check(&topics, &topic, &link).is_none());
+        }
 
         let link = ParsedLink {
-            target: "target.md#section".to_string(),
-            line: 1,
-            column: 1,
+            kind: LinkKind::Wiki,
+            ..markdown_link("target#Other", 1, 1)
         };
+        assert!(matches!(
+            check(&topics, &topic, &link),
+            Some(TopicWarning::BrokenAnchor { .. })
+        ));
 
-        // Should pass because file exists (anchor not validated)
-        assert!(check_link(&topic_path, &link).is_none());
+        let link = ParsedLink {
+            kind: LinkKind::Wiki,
+            ..markdown_link("missing", 2, 4)
+        };
+        assert_eq!(
+            check(&topics, &topic, &link),
+            Some(TopicWarning::BrokenLink {
+                target: "missing".to_string(),
+                line: 2,
+                column: 4,
+            })
+        );
+    }
+
+    #[test]
+    fn check_ambiguous_lists_candidates() {
+        let topics = vec![
+            Topic::from_content(Path::new("/n/projects/index.md"), "# Projects"),
+            Topic::from_content(Path::new("/n/areas/index.md"), "# Areas"),
+            Topic::from_content(Path::new("/n/projects/plan.md"), "# Plan"),
+        ];
+        let wiki = |target| ParsedLink {
+            kind: LinkKind::Wiki,
+            ..markdown_link(target, 4, 2)
+        };
+        let resolver = LinkResolver::new(&topics);
+
+        assert_eq!(
+            check_ambiguous(&resolver, &topics[2], &wiki("index#Goals")),
+            Some(TopicWarning::AmbiguousLink {
+                target: "index#Goals".to_string(),
+                candidates: vec!["index".to_string(), "../areas/index".to_string()],
+                line: 4,
+                column: 2,
+            })
+        );
+        assert!(check_ambiguous(&resolver, &topics[2], &wiki("areas/index")).is_none());
+        assert!(check_ambiguous(&resolver, &topics[2], &markdown_link("index.md", 1, 1)).is_none());
+    }
+
+    #[test]
+    fn check_graph_applies_enabled_rules() {
+        let topics = vec![
+            Topic::from_content(Path::new("/n/a.md"), "# A\n\n[[c]]"),
+            Topic::from_content(Path::new("/n/b.md"), "# B\n\n[[c]]"),
+            Topic::from_content(Path::new("/n/c.md"), "# C"),
+            Topic::from_content(Path::new("/n/d.md"), "# D"),
+        ];
+
+        let none = check_graph(&topics, &LintSettings::default());
+        assert!(none.iter().all(Vec::is_empty));
+
+        let rules = LintSettings {
+            orphans: true,
+            dead_ends: true,
+            hubs: true,
+            hub_threshold: 2,
+        };
+        assert_eq!(
+            check_graph(&topics, &rules),
+            vec![
+                vec![],
+                vec![],
+                vec![TopicWarning::DeadEnd, TopicWarning::Hub { linked_from: 2 }],
+                vec![TopicWarning::Orphan],
+            ]
+        );
     }
 }
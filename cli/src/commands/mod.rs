@@ -1,9 +1,18 @@
 pub mod backlinks;
 pub mod delete;
+pub mod graph;
 pub mod info;
+pub mod journal;
 pub mod lint;
 pub mod list;
+pub mod mv;
 pub mod new;
 pub mod open;
 pub mod rename;
 pub mod search;
+pub mod set;
+pub mod tasks;
+pub mod templates;
+pub mod trash;
+pub mod undo;
+pub mod unset;
@@ -0,0 +1,188 @@
+use anyhow::{bail, Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::cli::{DryRunFormat, SortOrder};
+use crate::constants::DATA_DIR;
+use crate::core::changes::ChangeSet;
+use crate::core::links::normalize;
+use crate::core::relink::Relocation;
+use crate::core::{history, index};
+use crate::utils::is_contained;
+
+/// Move a topic, or every topic matching `filter`, into a directory under
+/// the root, and update links to and from the moved topics.
+///
+/// `args` is the topic followed by the directory, or just the directory when
+/// a filter is given.
+pub fn run(
+    root: &Path,
+    args: &[String],
+    filter: Option<&str>,
+    dry_run: Option<DryRunFormat>,
+) -> Result<()> {
+    let command = history::describe_targets("move", filter, args);
+    let (paths, args) = index::targets(root, args, filter)?;
+    let dir = match args {
+        [dir] => dir,
+        [] => bail!("Expected a destination directory"),
+        _ => bail!(
+            "Expected one destination directory, got: {}",
+            args.join(" ")
+        ),
+    };
+    if !is_contained(Path::new(dir)) {
+        bail!("Destination must be a directory inside the root: {}", dir);
+    }
+    if is_reserved(Path::new(dir)) {
+        bail!("Can't move topics into {} or .git: {}", DATA_DIR, dir);
+    }
+    if filter.is_some() && paths.is_empty() {
+        println!("No topics found.");
+        return Ok(());
+    }
+
+    let dest_dir = root.join(dir);
+    let moves = destinations(&paths, &dest_dir)?;
+    if moves.is_empty() {
+        println!("Nothing to move.");
+        return Ok(());
+    }
+
+    let topics = index::list(root, SortOrder::Alpha)?;
+    let mut relocation = Relocation::new(&topics);
+    for (from, to) in &moves {
+        relocation.add(from, to, None);
+    }
+
+    let mut files: Vec<PathBuf> = topics.iter().map(|t| t.path.clone()).collect();
+    let known: HashSet<PathBuf> = files.iter().map(|p| normalize(p)).collect();
+    files.extend(
+        moves
+            .iter()
+            .filter(|(from, _)| !known.contains(&normalize(from)))
+            .map(|(from, _)| from.clone()),
+    );
+
+    let mut changes = ChangeSet::new();
+    let mut updated = Vec::new();
+    for path in &files {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let rewritten = relocation.rewrite(&content, path);
+        match moves
+            .iter()
+            .find(|(from, _)| normalize(from) == normalize(path))
+        {
+            Some((from, to)) => {
+                changes.write(to, rewritten)?;
+                changes.remove(from)?;
+            }
+            None if rewritten != content => {
+                changes.write(path, rewritten)?;
+                updated.push(path);
+            }
+            None => {}
+        }
+    }
+
+    if let Some(format) = dry_run {
+        return changes.print(root, format);
+    }
+    changes.commit(root, &command)?;
+
+    for (from, to) in &moves {
+        println!("Moved: {} -> {}", from.display(), to.display());
+    }
+    for path in &updated {
+        println!("  Updated links in: {}", path.display());
+    }
+
+    if !updated.is_empty() {
+        println!();
+        println!("Updated {} file(s) with new links", updated.len());
+    }
+
+    Ok(())
+}
+
+/// Pair each topic with its path in `dir`, skipping topics already there.
+///
+/// Fails if a destination is taken, or two topics would end up at the same path.
+fn destinations(paths: &[PathBuf], dir: &Path) -> Result<Vec<(PathBuf, PathBuf)>> {
+    let mut moves: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for path in paths {
+        let Some(filename) = path.file_name() else {
+            continue;
+        };
+        let to = dir.join(filename);
+        if normalize(&to) == normalize(path) {
+            continue;
+        }
+        if to.exists() {
+            bail!("A topic already exists at: {}", to.display());
+        }
+        if let Some((other, _)) = moves.iter().find(|(_, t)| *t == to) {
+            bail!(
+                "{} and {} would both move to: {}",
+                other.display(),
+                path.display(),
+                to.display()
+            );
+        }
+        moves.push((path.clone(), to));
+    }
+    Ok(moves)
+}
+
+/// Whether `dir` points into hypha's data directory or the git directory,
+/// where moved topics would drop out of the index.
+fn is_reserved(dir: &Path) -> bool {
+    dir.components()
+        .find(|c| c != &Component::CurDir)
+        .is_some_and(|c| c.as_os_str() == DATA_DIR || c.as_os_str() == ".git")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn destinations_skip_topics_in_place_and_reject_clashes() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("a")).unwrap();
+        fs::create_dir_all(root.join("archive")).unwrap();
+        fs::write(root.join("a/note.md"), "").unwrap();
+        fs::write(root.join("note.md"), "").unwrap();
+        fs::write(root.join("archive/done.md"), "").unwrap();
+
+        let dir = root.join("archive");
+        assert_eq!(
+            destinations(&[root.join("note.md"), root.join("archive/done.md")], &dir).unwrap(),
+            vec![(root.join("note.md"), dir.join("note.md"))]
+        );
+
+        let err = destinations(&[root.join("note.md"), root.join("a/note.md")], &dir)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("would both move to"), "{}", err);
+
+        fs::write(dir.join("note.md"), "").unwrap();
+        let err = destinations(&[root.join("note.md")], &dir)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("A topic already exists"), "{}", err);
+    }
+
+    #[test]
+    fn reserved_destinations() {
+        assert!(is_reserved(Path::new(".hypha")));
+        assert!(is_reserved(Path::new("./.hypha/trash")));
+        assert!(is_reserved(Path::new(".git/objects")));
+        assert!(!is_reserved(Path::new("archive/.hypha")));
+        assert!(!is_reserved(Path::new("projects")));
+    }
+}
@@ -1,27 +1,146 @@
-use anyhow::{bail, Context, Result};
-use std::fs;
+use anyhow::{bail, Result};
+use serde::Serialize;
 use std::path::Path;
 
-use crate::core::template;
-use crate::utils::slugify;
+use crate::cli::DryRunFormat;
+use crate::core::changes::ChangeSet;
+use crate::core::frontmatter;
+use crate::core::history;
+use crate::core::settings::Settings;
+use crate::core::template::{self, Template, Values};
+use crate::utils::{is_contained, read_stdin, slugify};
+
+/// How to create a topic.
+pub struct Options<'a> {
+    /// Named template from `.hypha/templates/`, instead of `.template.md`.
+    pub template: Option<&'a str>,
+    /// `name=value` answers for the template's `{prompt:Name}` placeholders.
+    pub vars: &'a [String],
+    /// `key=value` frontmatter fields to set on the new topic.
+    pub fields: &'a [String],
+    /// Directory under the root for the topic, instead of the template's folder.
+    pub dir: Option<&'a str>,
+    /// Text to add after the template, or `-` to read it from stdin.
+    pub body: Option<&'a str>,
+    pub open: bool,
+    /// Print only the new topic's path.
+    pub print_path: bool,
+    pub json: bool,
+}
+
+#[derive(Serialize)]
+struct CreatedJson<'a> {
+    title: &'a str,
+    path: String,
+}
+
+/// Create a new topic, from the root's template or a named one.
+///
+/// A named template's topics go in its `folder` from `[templates.<name>]`,
+/// or the root if it has none. Prompts without a `--var` are asked for.
+pub fn run(
+    root: &Path,
+    title: &str,
+    options: &Options,
+    dry_run: Option<DryRunFormat>,
+) -> Result<()> {
+    let settings = Settings::load(root)?;
+    let (text, folder) = match options.template {
+        Some(name) => (
+            template::load_named(root, name)?,
+            settings
+                .templates
+                .get(name)
+                .and_then(|t| t.folder.as_deref()),
+        ),
+        None => (template::load(root), None),
+    };
+    let dir = match options.dir.or(folder) {
+        Some(dir) if !is_contained(Path::new(dir)) => {
+            bail!("Directory must be inside the root: {}", dir)
+        }
+        Some(dir) => root.join(dir),
+        None => root.to_path_buf(),
+    };
 
-/// Create a new topic.
-pub fn run(root: &Path, title: &str, open_after: bool) -> Result<()> {
     let slug = slugify(title);
     let filename = format!("{}.md", slug);
-    let filepath = root.join(&filename);
+    let filepath = dir.join(&filename);
 
     if filepath.exists() {
         bail!("Topic already exists: {}", filepath.display());
     }
 
-    let tmpl = template::load(root);
-    let content = template::render(&tmpl, title);
+    let fields = options
+        .fields
+        .iter()
+        .map(|field| match field.split_once('=') {
+            Some((key, value)) => Ok((key.trim(), value)),
+            None => bail!("Expected --field key=value, got: {}", field),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let tmpl = Template::parse(&text)?;
+    let body_from_stdin = options.body == Some("-");
+    if body_from_stdin && tmpl.reads_stdin() {
+        bail!("--body - can't be used with a template that reads {{stdin}}");
+    }
+    let mut values = Values::gather(&tmpl, title, options.vars)?;
+    let body = match options.body {
+        Some("-") => Some(read_stdin("--body -")?),
+        Some(body) => Some(body.to_string()),
+        None => None,
+    };
+    if tmpl.reads_stdin() {
+        values.stdin = Some(read_stdin("The template's {stdin}")?);
+    }
+
+    let mut content = tmpl.render(&values)?;
+    for (key, value) in &fields {
+        content = frontmatter::set_field(&content, key, value)?;
+    }
+    if let Some(body) = body.filter(|b| !b.trim().is_empty()) {
+        content = format!("{}\n\n{}\n", content.trim_end(), body.trim_end());
+    }
+
+    let mut changes = ChangeSet::new();
+    changes.write(&filepath, content)?;
+    if let Some(format) = dry_run {
+        return changes.print(root, format);
+    }
+
+    let mut command = vec!["new"];
+    if let Some(name) = options.template {
+        command.extend(["--template", name]);
+    }
+    for var in options.vars {
+        command.extend(["--var", var]);
+    }
+    for field in options.fields {
+        command.extend(["--field", field]);
+    }
+    if let Some(dir) = options.dir {
+        command.extend(["--dir", dir]);
+    }
+    if let Some(body) = options.body {
+        command.extend(["--body", body]);
+    }
+    command.push(title);
+    changes.commit(root, &history::describe(&command))?;
 
-    fs::write(&filepath, content).context("Failed to write topic file")?;
-    println!("Created: {}", filepath.display());
+    if options.json {
+        let output = CreatedJson {
+            title,
+            path: filepath.to_string_lossy().to_string(),
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if options.print_path {
+        println!("{}", filepath.display());
+    } else {
+        println!("Created: {}", filepath.display());
+    }
 
-    if open_after {
+    if options.open {
         crate::commands::open::open_in_editor(&filepath)?;
     }
 
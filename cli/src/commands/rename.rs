@@ -2,12 +2,14 @@ use anyhow::{bail, Result};
 use std::fs;
 use std::path::Path;
 
-use crate::cli::SortOrder;
-use crate::core::index;
+use crate::cli::{DryRunFormat, SortOrder};
+use crate::core::changes::ChangeSet;
+use crate::core::relink::Relocation;
+use crate::core::{history, index};
 use crate::utils::slugify;
 
 /// Rename a topic and update all links pointing to it.
-pub fn run(root: &Path, from: &str, to: &str) -> Result<()> {
+pub fn run(root: &Path, from: &str, to: &str, dry_run: Option<DryRunFormat>) -> Result<()> {
     let topics = index::list(root, SortOrder::Alpha)?;
 
     // Find the source topic
@@ -25,102 +27,75 @@ pub fn run(root: &Path, from: &str, to: &str) -> Result<()> {
     };
 
     let old_path = source.path.clone();
-    let old_filename = old_path
-        .file_name()
-        .map(|s| s.to_string_lossy().to_string())
-        .unwrap_or_default();
-    let old_stem = old_path
-        .file_stem()
-        .map(|s| s.to_string_lossy().to_string())
-        .unwrap_or_default();
 
     // Generate new filename
-    let new_stem = slugify(to);
-    let new_filename = format!("{}.md", new_stem);
+    let new_filename = format!("{}.md", slugify(to));
     let new_path = old_path.parent().unwrap().join(&new_filename);
 
     if new_path.exists() {
         bail!("A topic already exists at: {}", new_path.display());
     }
 
-    // Read the source file and update the heading
-    let content = fs::read_to_string(&old_path)?;
-    let new_content = update_heading(&content, to);
-
-    // Write updated content to new path
-    fs::write(&new_path, new_content)?;
+    let mut relocation = Relocation::new(&topics);
+    relocation.add(&old_path, &new_path, Some(to.to_string()));
 
-    // Delete old file
-    fs::remove_file(&old_path)?;
+    // Read the source file and update its heading and links
+    let content = fs::read_to_string(&old_path)?;
+    let new_content = update_heading(&relocation.rewrite(&content, &old_path), to);
 
-    println!("Renamed: {} -> {}", old_path.display(), new_path.display());
+    let mut changes = ChangeSet::new();
+    changes.write(&new_path, new_content)?;
+    changes.remove(&old_path)?;
 
     // Update links in other topics
-    let mut updated_count = 0;
+    let mut updated = Vec::new();
     for t in &topics {
         if t.path == old_path {
             continue;
         }
 
         let content = fs::read_to_string(&t.path)?;
-        let updated = update_links(&content, &old_filename, &old_stem, &new_filename, &new_stem);
+        let rewritten = relocation.rewrite(&content, &t.path);
 
-        if updated != content {
-            fs::write(&t.path, updated)?;
-            updated_count += 1;
-            println!("  Updated links in: {}", t.path.display());
+        if rewritten != content {
+            changes.write(&t.path, rewritten)?;
+            updated.push(&t.path);
         }
     }
 
-    if updated_count > 0 {
+    if let Some(format) = dry_run {
+        return changes.print(root, format);
+    }
+    changes.commit(root, &history::describe(&["rename", from, to]))?;
+
+    println!("Renamed: {} -> {}", old_path.display(), new_path.display());
+    for path in &updated {
+        println!("  Updated links in: {}", path.display());
+    }
+
+    if !updated.is_empty() {
         println!();
-        println!("Updated {} file(s) with new links", updated_count);
+        println!("Updated {} file(s) with new links", updated.len());
     }
 
     Ok(())
 }
 
-/// Update the first heading in the content to the new title.
+/// Update the first heading in the content to the new title, keeping line
+/// endings as they were.
 fn update_heading(content: &str, new_title: &str) -> String {
-    let lines: Vec<&str> = content.lines().collect();
-    let mut result = Vec::new();
+    let mut result = String::with_capacity(content.len());
     let mut heading_updated = false;
 
-    for line in lines {
+    for line in content.split_inclusive('\n') {
         if !heading_updated && line.starts_with("# ") {
-            result.push(format!("# {}", new_title));
+            let ending = &line[line.trim_end_matches(['\r', '\n']).len()..];
+            result.push_str(&format!("# {}{}", new_title, ending));
             heading_updated = true;
         } else {
-            result.push(line.to_string());
+            result.push_str(line);
         }
     }
 
-    result.join("\n")
-}
-
-/// Update links from old filename to new filename.
-fn update_links(
-    content: &str,
-    old_filename: &str,
-    old_stem: &str,
-    new_filename: &str,
-    new_stem: &str,
-) -> String {
-    let mut result = content.to_string();
-
-    // Update markdown links: [text](old-file.md) -> [text](new-file.md)
-    result = result.replace(
-        &format!("]({})", old_filename),
-        &format!("]({})", new_filename),
-    );
-    result = result.replace(&format!("]({})", old_stem), &format!("]({})", new_stem));
-
-    // Update wiki links: [[old-file]] -> [[new-file]]
-    result = result.replace(
-        &format!("[[{}]]", old_filename.trim_end_matches(".md")),
-        &format!("[[{}]]", new_stem),
-    );
-    result = result.replace(&format!("[[{}]]", old_stem), &format!("[[{}]]", new_stem));
-
     result
 }
@@ -0,0 +1,94 @@
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cli::DryRunFormat;
+use crate::core::changes::ChangeSet;
+use crate::core::{frontmatter, history, index};
+
+/// Set frontmatter fields on a topic, or on every topic matching `filter`.
+///
+/// `args` is the topic followed by `key=value` pairs, or just the pairs when
+/// a filter is given.
+pub fn run(
+    root: &Path,
+    args: &[String],
+    filter: Option<&str>,
+    dry_run: Option<DryRunFormat>,
+) -> Result<()> {
+    let command = history::describe_targets("set", filter, args);
+    let (paths, args) = index::targets(root, args, filter)?;
+    if args.is_empty() {
+        bail!("Expected at least one key=value pair");
+    }
+
+    let fields = args
+        .iter()
+        .map(|arg| match arg.split_once('=') {
+            Some((key, value)) => Ok((key.trim(), value)),
+            None => bail!("Expected key=value, got: {}", arg),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    update(
+        root,
+        &paths,
+        &command,
+        filter.is_some(),
+        dry_run,
+        |content| {
+            let mut content = content.to_string();
+            for (key, value) in &fields {
+                content = frontmatter::set_field(&content, key, value)?;
+            }
+            Ok(content)
+        },
+    )
+}
+
+/// Apply an edit to each file, writing only files that change.
+///
+/// Every edit is computed before anything is written, so an invalid value
+/// or unreadable frontmatter leaves all files untouched.
+pub(crate) fn update(
+    root: &Path,
+    paths: &[PathBuf],
+    command: &str,
+    bulk: bool,
+    dry_run: Option<DryRunFormat>,
+    edit: impl Fn(&str) -> Result<String>,
+) -> Result<()> {
+    if bulk && paths.is_empty() {
+        println!("No topics found.");
+        return Ok(());
+    }
+
+    let mut changes = ChangeSet::new();
+    for path in paths {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let updated = edit(&content).with_context(|| format!("In {}", path.display()))?;
+        changes.write(path, updated)?;
+    }
+
+    if let Some(format) = dry_run {
+        return changes.print(root, format);
+    }
+    changes.commit(root, command)?;
+
+    for change in changes.changes() {
+        println!("Updated: {}", change.path().display());
+    }
+
+    if changes.is_empty() {
+        println!("Nothing to change.");
+    } else if bulk {
+        println!();
+        println!(
+            "Updated {} of {} topic(s)",
+            changes.changes().len(),
+            paths.len()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+
+use crate::cli::SortOrder;
+use crate::core::index;
+use crate::core::markdown::Task;
+use crate::core::query::{self, FieldValue, Query, Queryable};
+use crate::core::topic::Topic;
+
+#[derive(Serialize)]
+struct TaskJson<'a> {
+    topic: &'a str,
+    path: String,
+    line: usize,
+    done: bool,
+    text: &'a str,
+    due: Option<&'a str>,
+    owners: &'a [String],
+}
+
+/// A task with the topic it's in, for matching against a query.
+struct TopicTask<'a> {
+    topic: &'a Topic,
+    task: &'a Task,
+}
+
+impl Queryable for TopicTask<'_> {
+    /// `status` (`open` or `done`), `due` and `owner` are the task's; other
+    /// keys are the topic's, and `topic.` reaches a topic field that a task
+    /// field hides.
+    fn get_field(&self, key: &str) -> Option<FieldValue> {
+        match key {
+            "status" => Some(FieldValue::Single(
+                if self.task.done { "done" } else { "open" }.to_string(),
+            )),
+            "due" => self.task.due.clone().map(FieldValue::Single),
+            "owner" if self.task.owners.is_empty() => None,
+            "owner" => Some(FieldValue::Multiple(self.task.owners.clone())),
+            "topic" => Some(FieldValue::Single(self.topic.title.clone())),
+            _ => self
+                .topic
+                .get_field(key.strip_prefix("topic.").unwrap_or(key)),
+        }
+    }
+
+    fn searchable_text(&self) -> String {
+        self.task.text.clone()
+    }
+}
+
+/// List tasks from every topic that match `query`, grouped by topic.
+pub fn run(root: &Path, query: &str, json: bool) -> Result<()> {
+    let query = Query::parse(query)?;
+    let topics = index::list(root, SortOrder::Alpha)?;
+
+    let matching: Vec<TopicTask> = topics
+        .iter()
+        .flat_map(|topic| {
+            topic
+                .tasks
+                .iter()
+                .map(move |task| TopicTask { topic, task })
+        })
+        .filter(|item| query::matches(item, &query))
+        .collect();
+
+    if json {
+        let output: Vec<TaskJson> = matching
+            .iter()
+            .map(|item| TaskJson {
+                topic: &item.topic.title,
+                path: item.topic.path.display().to_string(),
+                line: item.task.line,
+                done: item.task.done,
+                text: &item.task.text,
+                due: item.task.due.as_deref(),
+                owners: &item.task.owners,
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if matching.is_empty() {
+        println!("No tasks found.");
+        return Ok(());
+    }
+
+    let mut current: Option<&Path> = None;
+    for item in &matching {
+        if current != Some(item.topic.path.as_path()) {
+            if current.is_some() {
+                println!();
+            }
+            println!("{} ({})", item.topic.title, item.topic.path.display());
+            current = Some(&item.topic.path);
+        }
+        let check = if item.task.done { "x" } else { " " };
+        println!("  [{}] {} (line {})", check, item.task.text, item.task.line);
+    }
+
+    let open = matching.iter().filter(|item| !item.task.done).count();
+    println!();
+    println!("{} task(s), {} open", matching.len(), open);
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use crate::core::settings::Settings;
+use crate::core::template::{self, Template};
+
+#[derive(Serialize)]
+struct TemplateJson {
+    name: String,
+    path: String,
+    folder: Option<String>,
+    description: Option<String>,
+    /// Labels of the template's `{prompt:...}` placeholders.
+    prompts: Vec<String>,
+}
+
+/// List named templates with their target folders and descriptions.
+pub fn list(root: &Path, json: bool) -> Result<()> {
+    let templates = template::list(root)?;
+    let settings = Settings::load(root)?;
+
+    let output: Vec<TemplateJson> = templates
+        .into_iter()
+        .map(|t| {
+            let options = settings.templates.get(&t.name);
+            // Invalid templates are still listed; `hypha new` reports the error
+            let prompts = fs::read_to_string(&t.path)
+                .ok()
+                .and_then(|text| Template::parse(&text).ok())
+                .map(|tmpl| tmpl.prompts().into_iter().map(String::from).collect())
+                .unwrap_or_default();
+            TemplateJson {
+                prompts,
+                folder: options.and_then(|o| o.folder.clone()),
+                description: options.and_then(|o| o.description.clone()),
+                path: t.path.to_string_lossy().to_string(),
+                name: t.name,
+            }
+        })
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if output.is_empty() {
+        println!("No templates in {}", template::dir(root).display());
+        return Ok(());
+    }
+
+    let width = output.iter().map(|t| t.name.len()).max().unwrap_or(0);
+    for t in &output {
+        let mut line = format!("  {:width$}", t.name, width = width);
+        if let Some(description) = &t.description {
+            line.push_str(&format!("  {}", description));
+        }
+        if let Some(folder) = &t.folder {
+            line.push_str(&format!("  (in {}/)", folder.trim_end_matches('/')));
+        }
+        println!("{}", line.trim_end());
+    }
+    Ok(())
+}
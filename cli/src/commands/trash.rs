@@ -0,0 +1,203 @@
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use crate::cli::DryRunFormat;
+use crate::core::changes::ChangeSet;
+use crate::core::history;
+use crate::core::trash::{self, Trashed};
+use crate::utils::confirm;
+
+#[derive(Serialize)]
+struct TrashedJson {
+    number: usize,
+    path: String,
+    trash_path: String,
+    deleted: Option<String>,
+}
+
+/// List deleted topics, most recent first.
+pub fn list(root: &Path, json: bool) -> Result<()> {
+    let trashed = trash::list(root)?;
+
+    if json {
+        let output: Vec<TrashedJson> = trashed
+            .iter()
+            .enumerate()
+            .map(|(i, t)| TrashedJson {
+                number: i + 1,
+                path: t.original.to_string_lossy().to_string(),
+                trash_path: t.path.to_string_lossy().to_string(),
+                deleted: t
+                    .deleted()
+                    .map(|d| d.format("%Y-%m-%dT%H:%M:%S").to_string()),
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if trashed.is_empty() {
+        println!("Trash is empty.");
+        return Ok(());
+    }
+
+    for (i, t) in trashed.iter().enumerate() {
+        match t.deleted() {
+            Some(deleted) => println!(
+                "{:>3}. {} (deleted {})",
+                i + 1,
+                t.original.display(),
+                deleted.format("%Y-%m-%d %H:%M")
+            ),
+            None => println!("{:>3}. {}", i + 1, t.original.display()),
+        }
+    }
+    println!();
+    println!("{} topic(s) in trash", trashed.len());
+    Ok(())
+}
+
+/// Put a deleted topic back where it was.
+///
+/// `item` is the topic's original path or filename, or a number from
+/// `hypha trash list`. An exact path wins; otherwise, if several deleted
+/// topics have the filename, the most recent is restored. A bare number is
+/// only read as a list position when no topic has that name; `#N` always is.
+pub fn restore(root: &Path, item: &str, dry_run: Option<DryRunFormat>) -> Result<()> {
+    let trashed = trash::list(root)?;
+    let Some(entry) = find(&trashed, item) else {
+        bail!("No deleted topic matching: {}", item);
+    };
+
+    let destination = root.join(&entry.original);
+    if destination.exists() {
+        bail!("A topic already exists at: {}", destination.display());
+    }
+
+    let content = fs::read_to_string(&entry.path)
+        .with_context(|| format!("Failed to read {}", entry.path.display()))?;
+    let mut changes = ChangeSet::new();
+    changes.write(&destination, content)?;
+    changes.remove(&entry.path)?;
+
+    if let Some(format) = dry_run {
+        return changes.print(root, format);
+    }
+    changes.commit(root, &history::describe(&["trash", "restore", item]))?;
+    if let Some(parent) = entry.path.parent() {
+        trash::prune(root, parent);
+    }
+
+    println!("Restored: {}", destination.display());
+    Ok(())
+}
+
+/// Permanently delete everything in the trash. This can't be undone.
+pub fn empty(root: &Path, yes: bool, dry_run: Option<DryRunFormat>) -> Result<()> {
+    let trashed = trash::list(root)?;
+    if trashed.is_empty() && dry_run.is_none() {
+        println!("Trash is empty.");
+        return Ok(());
+    }
+
+    let mut changes = ChangeSet::new();
+    for t in &trashed {
+        changes.remove(&t.path)?;
+    }
+    if let Some(format) = dry_run {
+        return changes.print(root, format);
+    }
+
+    if !yes
+        && !confirm(&format!(
+            "Permanently delete {} topic(s) in the trash?",
+            trashed.len()
+        ))?
+    {
+        println!("Cancelled.");
+        return Ok(());
+    }
+
+    changes.apply()?;
+    let dir = trash::dir(root);
+    fs::remove_dir_all(&dir).with_context(|| format!("Failed to remove {}", dir.display()))?;
+    println!("Emptied trash: {} topic(s) deleted", trashed.len());
+    Ok(())
+}
+
+/// Find a trashed topic by list number, original path or filename.
+fn find<'a>(trashed: &'a [Trashed], item: &str) -> Option<&'a Trashed> {
+    let number = |n: &str| {
+        n.parse::<usize>()
+            .ok()
+            .and_then(|n| n.checked_sub(1))
+            .and_then(|i| trashed.get(i))
+    };
+    if let Some(n) = item.strip_prefix('#') {
+        return number(n);
+    }
+
+    let name = item.strip_suffix(".md").unwrap_or(item);
+    let original = |t: &Trashed| t.original.with_extension("");
+    trashed
+        .iter()
+        .find(|t| original(t).to_string_lossy().replace('\\', "/") == name)
+        .or_else(|| {
+            trashed.iter().find(|t| {
+                original(t)
+                    .file_name()
+                    .is_some_and(|file| file.to_string_lossy().eq_ignore_ascii_case(name))
+            })
+        })
+        .or_else(|| number(item))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn trashed(id: &str, original: &str) -> Trashed {
+        Trashed {
+            id: id.to_string(),
+            original: PathBuf::from(original),
+            path: PathBuf::from(format!(".hypha/trash/{}/{}", id, original)),
+        }
+    }
+
+    #[test]
+    fn finds_by_number_path_or_filename() {
+        let items = vec![
+            trashed("20260301-120000", "projects/plan.md"),
+            trashed("20260101-090000", "notes.md"),
+            trashed("20251201-090000", "plan.md"),
+        ];
+
+        assert_eq!(find(&items, "2"), Some(&items[1]));
+        assert_eq!(find(&items, "4"), None);
+        assert_eq!(find(&items, "0"), None);
+        assert_eq!(find(&items, "projects/plan.md"), Some(&items[0]));
+        assert_eq!(find(&items, "plan.md"), Some(&items[2]));
+        assert_eq!(find(&items, "Plan"), Some(&items[0]));
+        assert_eq!(find(&items, "missing"), None);
+        assert_eq!(find(&items, "#2"), Some(&items[1]));
+        assert_eq!(find(&items, "#x"), None);
+    }
+
+    #[test]
+    fn prefers_names_over_numbers() {
+        let items = vec![
+            trashed("20260301-120000", "notes.md"),
+            trashed("20260101-090000", "2.md"),
+            trashed("20251201-090000", "plan.md"),
+        ];
+
+        assert_eq!(find(&items, "2"), Some(&items[1]));
+        assert_eq!(find(&items, "2.md"), Some(&items[1]));
+        assert_eq!(find(&items, "#2"), Some(&items[1]));
+        assert_eq!(find(&items, "3"), Some(&items[2]));
+        assert_eq!(find(&items, "#3"), Some(&items[2]));
+    }
+}
@@ -0,0 +1,30 @@
+use anyhow::Result;
+use std::path::Path;
+
+use crate::cli::DryRunFormat;
+use crate::core::changes::Change;
+use crate::core::history;
+
+/// Revert the most recent change made by a hypha command.
+pub fn run(root: &Path, dry_run: Option<DryRunFormat>) -> Result<()> {
+    let Some(entry) = history::last(root)? else {
+        println!("Nothing to undo.");
+        return Ok(());
+    };
+
+    let changes = entry.revert(root)?;
+    if let Some(format) = dry_run {
+        return changes.print(root, format);
+    }
+    changes.apply()?;
+    history::pop(root)?;
+
+    println!("Undid: {}", entry.command);
+    for change in changes.changes() {
+        match change {
+            Change::Write { path, .. } => println!("  Restored: {}", path.display()),
+            Change::Remove { path, .. } => println!("  Removed: {}", path.display()),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use anyhow::{bail, Result};
+use std::path::Path;
+
+use super::set;
+use crate::cli::DryRunFormat;
+use crate::core::{frontmatter, history, index};
+
+/// Remove frontmatter fields from a topic, or from every topic matching `filter`.
+///
+/// `args` is the topic followed by field names, or just the names when a
+/// filter is given.
+pub fn run(
+    root: &Path,
+    args: &[String],
+    filter: Option<&str>,
+    dry_run: Option<DryRunFormat>,
+) -> Result<()> {
+    let (paths, keys) = index::targets(root, args, filter)?;
+    if keys.is_empty() {
+        bail!("Expected at least one field name");
+    }
+
+    let command = history::describe_targets("unset", filter, args);
+    set::update(
+        root,
+        &paths,
+        &command,
+        filter.is_some(),
+        dry_run,
+        |content| {
+            let mut content = content.to_string();
+            for key in keys {
+                if let Some(updated) = frontmatter::remove_field(&content, key)? {
+                    content = updated;
+                }
+            }
+            Ok(content)
+        },
+    )
+}
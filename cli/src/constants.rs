@@ -4,6 +4,38 @@ pub const PROJECT_NAME: &str = "hypha";
 /// Template file name stored in the notes directory
 pub const TEMPLATE_FILE: &str = ".template.md";
 
+/// Directory in the notes root for Hypha's own data (cache, etc.); never indexed
+pub const DATA_DIR: &str = ".hypha";
+
+/// Index cache file name, stored in `DATA_DIR`
+pub const INDEX_CACHE_FILE: &str = "index.json";
+
+/// Undo history file name, stored in `DATA_DIR`
+pub const HISTORY_FILE: &str = "history.json";
+
+/// Named templates directory, stored in `DATA_DIR`
+pub const TEMPLATES_DIR: &str = "templates";
+
+/// Trash directory for deleted topics, stored in `DATA_DIR`
+pub const TRASH_DIR: &str = "trash";
+
+/// Per-root settings file name, stored in `DATA_DIR`
+pub const SETTINGS_FILE: &str = "config.toml";
+
+/// Frontmatter schema file name, stored in `DATA_DIR`
+pub const SCHEMA_FILE: &str = "schema.toml";
+
+/// Ignore file (gitignore syntax) for files that shouldn't be indexed
+pub const IGNORE_FILE: &str = ".hyphaignore";
+
+/// Default template for journal notes, when the period has no named template
+pub const DEFAULT_JOURNAL_TEMPLATE: &str = r#"---
+---
+
+# {title}
+
+"#;
+
 /// Default template for new topics. Placeholder: {title}
 pub const DEFAULT_TEMPLATE: &str = r#"---
 ---
@@ -0,0 +1,187 @@
+//! Persistent index cache.
+//!
+//! Parsed topics are stored in `<root>/.hypha/index.json`, keyed by path and
+//! validated against each file's modification time and size, so unchanged
+//! files are not re-read on every command. The cache is purely an
+//! optimisation: a missing, stale or corrupt cache is silently rebuilt.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::topic::Topic;
+use crate::constants::{DATA_DIR, INDEX_CACHE_FILE};
+
+/// Cache format version. Bump when `Topic` or the parsing that produces it changes,
+/// so caches written by older builds are discarded.
+const CACHE_VERSION: u32 = 7;
+
+/// A cached topic together with the file state it was parsed from.
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    modified: Option<SystemTime>,
+    size: u64,
+    topic: Topic,
+}
+
+/// On-disk representation of the cache.
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    version: String,
+    entries: HashMap<PathBuf, CacheEntry>,
+}
+
+/// Version string stored in the cache file.
+fn cache_version() -> String {
+    format!("{}+{}", env!("CARGO_PKG_VERSION"), CACHE_VERSION)
+}
+
+/// Index cache for a single root directory.
+pub struct IndexCache {
+    path: PathBuf,
+    entries: HashMap<PathBuf, CacheEntry>,
+    seen: HashSet<PathBuf>,
+    dirty: bool,
+}
+
+impl IndexCache {
+    /// Load the cache for a root, starting empty if it is missing or unusable.
+    pub fn load(root: &Path) -> Self {
+        let path = root.join(DATA_DIR).join(INDEX_CACHE_FILE);
+        let entries = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<CacheFile>(&content).ok())
+            .filter(|file| file.version == cache_version())
+            .map(|file| file.entries)
+            .unwrap_or_default();
+
+        IndexCache {
+            path,
+            entries,
+            seen: HashSet::new(),
+            dirty: false,
+        }
+    }
+
+    /// Get the topic for a file, re-parsing it only if it changed since it was cached.
+    pub fn topic(&mut self, path: &Path, metadata: &fs::Metadata) -> Result<Topic> {
+        let modified = metadata.modified().ok();
+        let size = metadata.len();
+        self.seen.insert(path.to_path_buf());
+
+        if let Some(entry) = self.entries.get(path) {
+            if entry.modified == modified && entry.size == size && modified.is_some() {
+                return Ok(entry.topic.clone());
+            }
+        }
+
+        let topic = Topic::from_path(path)?;
+        self.entries.insert(
+            path.to_path_buf(),
+            CacheEntry {
+                modified,
+                size,
+                topic: topic.clone(),
+            },
+        );
+        self.dirty = true;
+        Ok(topic)
+    }
+
+    /// Drop entries for files not visited since loading, then write the cache if it changed.
+    pub fn save(mut self) -> Result<()> {
+        let before = self.entries.len();
+        let seen = &self.seen;
+        self.entries.retain(|path, _| seen.contains(path));
+        if !self.dirty && self.entries.len() == before {
+            return Ok(());
+        }
+
+        let dir = self.path.parent().context("Invalid cache path")?;
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+        let file = CacheFile {
+            version: cache_version(),
+            entries: self.entries,
+        };
+        let content = serde_json::to_string(&file).context("Failed to serialize index cache")?;
+
+        // Write to a process-unique temp file and rename, so concurrent
+        // commands never observe a partially written cache.
+        let tmp = self
+            .path
+            .with_extension(format!("json.{}.tmp", std::process::id()));
+        fs::write(&tmp, content).with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn cached_topic(root: &Path, file: &Path) -> (Topic, bool) {
+        let mut cache = IndexCache::load(root);
+        let metadata = fs::metadata(file).unwrap();
+        let topic = cache.topic(file, &metadata).unwrap();
+        let dirty = cache.dirty;
+        cache.save().unwrap();
+        (topic, dirty)
+    }
+
+    #[test]
+    fn reuses_unchanged_topics() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("a.md");
+        fs::write(&file, "# First").unwrap();
+
+        let (topic, dirty) = cached_topic(temp.path(), &file);
+        assert_eq!(topic.title, "First");
+        assert!(dirty);
+        assert!(temp.path().join(DATA_DIR).join(INDEX_CACHE_FILE).exists());
+
+        let (topic, dirty) = cached_topic(temp.path(), &file);
+        assert_eq!(topic.title, "First");
+        assert!(!dirty, "Unchanged file should be served from cache");
+    }
+
+    #[test]
+    fn reparses_changed_topics() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("a.md");
+        fs::write(&file, "# First").unwrap();
+        cached_topic(temp.path(), &file);
+
+        fs::write(&file, "# Second, longer").unwrap();
+        let (topic, dirty) = cached_topic(temp.path(), &file);
+        assert_eq!(topic.title, "Second, longer");
+        assert!(dirty);
+    }
+
+    #[test]
+    fn prunes_unseen_entries() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("a.md");
+        fs::write(&file, "# First").unwrap();
+        cached_topic(temp.path(), &file);
+
+        IndexCache::load(temp.path()).save().unwrap();
+        assert!(IndexCache::load(temp.path()).entries.is_empty());
+    }
+
+    #[test]
+    fn ignores_corrupt_cache() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join(DATA_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(INDEX_CACHE_FILE), "not json").unwrap();
+
+        assert!(IndexCache::load(temp.path()).entries.is_empty());
+    }
+}
@@ -0,0 +1,439 @@
+//! Pending file changes.
+//!
+//! Mutating commands collect their writes and removals in a [`ChangeSet`],
+//! then either commit it (applying it atomically and recording it for
+//! `hypha undo`) or, with `--dry-run`, print what it would do.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use similar::TextDiff;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::history;
+use crate::cli::DryRunFormat;
+
+/// A single file change, with the file's content before it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// Create or overwrite a file. `before` is `None` for a new file.
+    Write {
+        path: PathBuf,
+        before: Option<String>,
+        after: String,
+    },
+    /// Delete a file.
+    Remove { path: PathBuf, before: String },
+}
+
+impl Change {
+    pub fn path(&self) -> &Path {
+        match self {
+            Change::Write { path, .. } | Change::Remove { path, .. } => path,
+        }
+    }
+
+    fn action(&self) -> &'static str {
+        match self {
+            Change::Write { before: None, .. } => "create",
+            Change::Write { .. } => "modify",
+            Change::Remove { .. } => "delete",
+        }
+    }
+
+    /// Unified diff of the change, with paths shown relative to `root`.
+    fn diff(&self, root: &Path) -> String {
+        let name = self
+            .path()
+            .strip_prefix(root)
+            .unwrap_or(self.path())
+            .to_string_lossy()
+            .replace('\\', "/");
+        let (before, after, old_name, new_name) = match self {
+            Change::Write {
+                before: None,
+                after,
+                ..
+            } => (
+                "",
+                after.as_str(),
+                "/dev/null".to_string(),
+                format!("b/{}", name),
+            ),
+            Change::Write {
+                before: Some(before),
+                after,
+                ..
+            } => (
+                before.as_str(),
+                after.as_str(),
+                format!("a/{}", name),
+                format!("b/{}", name),
+            ),
+            Change::Remove { before, .. } => (
+                before.as_str(),
+                "",
+                format!("a/{}", name),
+                "/dev/null".to_string(),
+            ),
+        };
+        TextDiff::from_lines(before, after)
+            .unified_diff()
+            .context_radius(3)
+            .header(&old_name, &new_name)
+            .to_string()
+    }
+}
+
+#[derive(Serialize)]
+struct ChangeJson {
+    action: &'static str,
+    path: String,
+    diff: String,
+}
+
+/// File changes made by one command, in the order they're applied.
+#[derive(Debug, Default)]
+pub struct ChangeSet {
+    changes: Vec<Change>,
+}
+
+impl ChangeSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write `content` to `path`, creating the file and its directories if
+    /// needed. Writes that leave the file unchanged are dropped, and a later
+    /// change to the same path replaces the earlier one.
+    pub fn write(&mut self, path: &Path, content: String) -> Result<()> {
+        let before = match self.take(path) {
+            Some(Change::Write { before, .. }) => before,
+            Some(Change::Remove { before, .. }) => Some(before),
+            None if path.exists() => Some(
+                fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?,
+            ),
+            None => None,
+        };
+        if before.as_deref() != Some(content.as_str()) {
+            self.changes.push(Change::Write {
+                path: path.to_path_buf(),
+                before,
+                after: content,
+            });
+        }
+        Ok(())
+    }
+
+    /// Delete the file at `path`. Removing a file this change set creates
+    /// drops the creation instead.
+    pub fn remove(&mut self, path: &Path) -> Result<()> {
+        let before = match self.take(path) {
+            Some(Change::Write { before: None, .. }) => return Ok(()),
+            Some(Change::Write {
+                before: Some(before),
+                ..
+            })
+            | Some(Change::Remove { before, .. }) => before,
+            None => fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?,
+        };
+        self.changes.push(Change::Remove {
+            path: path.to_path_buf(),
+            before,
+        });
+        Ok(())
+    }
+
+    /// Remove and return the pending change to `path`, if there is one.
+    fn take(&mut self, path: &Path) -> Option<Change> {
+        let i = self.changes.iter().position(|c| c.path() == path)?;
+        Some(self.changes.remove(i))
+    }
+
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Make the changes on disk, all or nothing.
+    ///
+    /// New content is first written to temp files beside each target, then
+    /// moved into place. If any step fails, files already changed are put
+    /// back as they were.
+    pub fn apply(&self) -> Result<()> {
+        let mut created = Vec::new();
+        let mut staged: Vec<Option<PathBuf>> = Vec::new();
+        for change in &self.changes {
+            let tmp = match change {
+                Change::Write { path, after, .. } => match stage(path, after, &mut created) {
+                    Ok(tmp) => Some(tmp),
+                    Err(err) => {
+                        discard(staged.iter().flatten());
+                        remove_dirs(&created);
+                        return Err(err);
+                    }
+                },
+                Change::Remove { .. } => None,
+            };
+            staged.push(tmp);
+        }
+
+        for (i, (change, tmp)) in self.changes.iter().zip(&staged).enumerate() {
+            let result = match (change, tmp) {
+                (Change::Write { path, .. }, Some(tmp)) => fs::rename(tmp, path)
+                    .with_context(|| format!("Failed to write {}", path.display())),
+                (Change::Remove { path, .. }, _) => fs::remove_file(path)
+                    .with_context(|| format!("Failed to delete {}", path.display())),
+                (Change::Write { .. }, None) => unreachable!("every write is staged"),
+            };
+            if let Err(err) = result {
+                discard(staged[i..].iter().flatten());
+                let failed = rollback(&self.changes[..i]);
+                remove_dirs(&created);
+                if failed.is_empty() {
+                    return Err(err.context("No changes were made"));
+                }
+                return Err(err.context(format!(
+                    "Some changes couldn't be reverted: {}",
+                    failed.join(", ")
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply the changes and record them in the undo history as `command`.
+    pub fn commit(&self, root: &Path, command: &str) -> Result<()> {
+        self.apply()?;
+        history::record(root, command, self).context("Failed to record changes for undo")
+    }
+
+    /// Unified diff of every change, with paths relative to `root`.
+    pub fn diff(&self, root: &Path) -> String {
+        self.changes.iter().map(|c| c.diff(root)).collect()
+    }
+
+    /// Print the changes instead of making them, for `--dry-run`.
+    pub fn print(&self, root: &Path, format: DryRunFormat) -> Result<()> {
+        match format {
+            DryRunFormat::Diff if self.is_empty() => println!("No changes."),
+            DryRunFormat::Diff => print!("{}", self.diff(root)),
+            DryRunFormat::Json => {
+                let output: Vec<ChangeJson> = self
+                    .changes
+                    .iter()
+                    .map(|c| ChangeJson {
+                        action: c.action(),
+                        path: c.path().to_string_lossy().to_string(),
+                        diff: c.diff(root),
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write `content` to a temp file next to `path`, creating directories as
+/// needed. Directories that didn't exist are added to `created`, parents
+/// first.
+fn stage(path: &Path, content: &str, created: &mut Vec<PathBuf>) -> Result<PathBuf> {
+    if let Some(parent) = path.parent() {
+        let missing: Vec<&Path> = parent.ancestors().take_while(|dir| !dir.exists()).collect();
+        created.extend(missing.iter().rev().map(|dir| dir.to_path_buf()));
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let name = path.file_name().context("Invalid file path")?;
+    let tmp = path.with_file_name(format!(
+        ".{}.{}.tmp",
+        name.to_string_lossy(),
+        std::process::id()
+    ));
+    fs::write(&tmp, content).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    Ok(tmp)
+}
+
+/// Remove leftover temp files.
+fn discard<'a>(tmps: impl Iterator<Item = &'a PathBuf>) {
+    for tmp in tmps {
+        let _ = fs::remove_file(tmp);
+    }
+}
+
+/// Remove directories created while staging, deepest first. Directories
+/// that aren't empty are left alone.
+fn remove_dirs(created: &[PathBuf]) {
+    for dir in created.iter().rev() {
+        let _ = fs::remove_dir(dir);
+    }
+}
+
+/// Undo applied changes, newest first, returning the paths that couldn't be restored.
+fn rollback(applied: &[Change]) -> Vec<String> {
+    let mut failed = Vec::new();
+    for change in applied.iter().rev() {
+        let result = match change {
+            Change::Write {
+                path, before: None, ..
+            } => fs::remove_file(path),
+            Change::Write {
+                path,
+                before: Some(before),
+                ..
+            }
+            | Change::Remove { path, before } => fs::write(path, before),
+        };
+        if result.is_err() {
+            failed.push(change.path().display().to_string());
+        }
+    }
+    failed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn diffs_writes_and_removals() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(root.join("a.md"), "# A\n\nOld line\n").unwrap();
+        fs::write(root.join("b.md"), "# B\n").unwrap();
+
+        let mut changes = ChangeSet::new();
+        changes
+            .write(&root.join("a.md"), "# A\n\nNew line\n".to_string())
+            .unwrap();
+        changes
+            .write(&root.join("b.md"), "# B\n".to_string())
+            .unwrap();
+        changes
+            .write(&root.join("dir/c.md"), "# C\n".to_string())
+            .unwrap();
+        changes.remove(&root.join("b.md")).unwrap();
+
+        assert_eq!(changes.changes().len(), 3, "unchanged writes are dropped");
+        assert_eq!(
+            changes.diff(root),
+            "--- a/a.md\n+++ b/a.md\n@@ -1,3 +1,3 @@\n # A\n \n-Old line\n+New line\n\
+             --- /dev/null\n+++ b/dir/c.md\n@@ -0,0 +1 @@\n+# C\n\
+             --- a/b.md\n+++ /dev/null\n@@ -1 +0,0 @@\n-# B\n"
+        );
+    }
+
+    #[test]
+    fn applies_changes() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(root.join("old.md"), "# Old\n").unwrap();
+
+        let mut changes = ChangeSet::new();
+        changes
+            .write(&root.join("dir/new.md"), "# New\n".to_string())
+            .unwrap();
+        changes.remove(&root.join("old.md")).unwrap();
+        changes.apply().unwrap();
+
+        assert!(!root.join("old.md").exists());
+        assert_eq!(
+            fs::read_to_string(root.join("dir/new.md")).unwrap(),
+            "# New\n"
+        );
+    }
+
+    #[test]
+    fn failed_changes_are_rolled_back() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(root.join("a.md"), "# A\n").unwrap();
+        fs::write(root.join("b.md"), "# B\n").unwrap();
+        fs::write(root.join("blocker"), "").unwrap();
+
+        // Staging fails: a directory can't be created over a file
+        let mut changes = ChangeSet::new();
+        changes
+            .write(&root.join("a.md"), "# A2\n".to_string())
+            .unwrap();
+        changes
+            .write(&root.join("blocker/c.md"), "# C\n".to_string())
+            .unwrap();
+        assert!(changes.apply().is_err());
+        assert_eq!(fs::read_to_string(root.join("a.md")).unwrap(), "# A\n");
+
+        // Applying fails part way: the file to delete has already gone
+        let mut changes = ChangeSet::new();
+        changes
+            .write(&root.join("a.md"), "# A2\n".to_string())
+            .unwrap();
+        changes
+            .write(&root.join("new.md"), "# New\n".to_string())
+            .unwrap();
+        changes.remove(&root.join("b.md")).unwrap();
+        fs::remove_file(root.join("b.md")).unwrap();
+        let err = changes.apply().unwrap_err();
+        assert!(format!("{:#}", err).contains("No changes were made"));
+
+        assert_eq!(fs::read_to_string(root.join("a.md")).unwrap(), "# A\n");
+        assert!(!root.join("new.md").exists());
+        let mut names: Vec<String> = fs::read_dir(root)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["a.md", "blocker"], "no temp files are left");
+
+        // Directories created for new files are removed again
+        let mut changes = ChangeSet::new();
+        changes
+            .write(&root.join("x/y/new.md"), "# New\n".to_string())
+            .unwrap();
+        changes.remove(&root.join("a.md")).unwrap();
+        fs::remove_file(root.join("a.md")).unwrap();
+        assert!(changes.apply().is_err());
+        assert!(!root.join("x").exists());
+    }
+
+    #[test]
+    fn repeated_changes_to_a_path_are_merged() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let a = root.join("a.md");
+        fs::write(&a, "# A\n").unwrap();
+
+        let mut changes = ChangeSet::new();
+        changes.write(&a, "# A2\n".to_string()).unwrap();
+        changes.write(&a, "# A3\n".to_string()).unwrap();
+        assert_eq!(
+            changes.changes(),
+            [Change::Write {
+                path: a.clone(),
+                before: Some("# A\n".to_string()),
+                after: "# A3\n".to_string(),
+            }]
+        );
+        changes.write(&a, "# A\n".to_string()).unwrap();
+        assert!(
+            changes.is_empty(),
+            "writing the original content back is no change"
+        );
+
+        let new = root.join("new.md");
+        changes.write(&new, "# New\n".to_string()).unwrap();
+        changes.remove(&new).unwrap();
+        assert!(changes.is_empty());
+
+        changes.remove(&a).unwrap();
+        changes.write(&a, "# A2\n".to_string()).unwrap();
+        changes.apply().unwrap();
+        assert_eq!(fs::read_to_string(&a).unwrap(), "# A2\n");
+    }
+}
@@ -1,12 +1,39 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
 use std::collections::HashMap;
+use std::ops::Range;
+
+/// Location of a frontmatter field in the file (1-based line and column).
+///
+/// The column points at the value when it is on the same line as the key,
+/// otherwise at the key.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FieldPosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A YAML error in a frontmatter block, positioned in the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrontmatterError {
+    /// YAML error message, without serde_yaml's block-relative positions.
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
 
 /// Result of parsing frontmatter.
 pub struct FrontmatterResult {
     /// Parsed frontmatter key-value pairs.
     pub metadata: HashMap<String, serde_yaml::Value>,
+    /// File-absolute positions of top-level frontmatter keys.
+    pub positions: HashMap<String, FieldPosition>,
+    /// Why the frontmatter block couldn't be parsed, if it couldn't.
+    pub error: Option<FrontmatterError>,
     /// The body content (after frontmatter, with leading newlines trimmed).
     pub body: String,
-    /// Number of newlines before body content starts.
+    /// Number of newlines before body content starts (including blank lines before the frontmatter).
     /// Add this to body-relative line numbers to get file-absolute line numbers.
     pub frontmatter_lines: usize,
 }
@@ -14,14 +41,23 @@ pub struct FrontmatterResult {
 /// Parse YAML frontmatter from markdown content.
 ///
 /// Returns frontmatter metadata, body content, and line count.
-/// If no valid frontmatter is found, returns empty HashMap and full content.
+/// If no frontmatter block is found, returns empty HashMap and full content.
+/// If the block isn't valid YAML, the metadata is empty, the body still
+/// starts after the block and `error` says what went wrong.
 pub fn parse(content: &str) -> FrontmatterResult {
-    let content = content.trim_start();
+    let trimmed = content.trim_start();
+    let leading_lines = content[..content.len() - trimmed.len()]
+        .chars()
+        .filter(|&c| c == '\n')
+        .count();
+    let content = trimmed;
     if !content.starts_with("---") {
         return FrontmatterResult {
             metadata: HashMap::new(),
+            positions: HashMap::new(),
+            error: None,
             body: content.to_string(),
-            frontmatter_lines: 0,
+            frontmatter_lines: leading_lines,
         };
     }
 
@@ -32,10 +68,11 @@ pub fn parse(content: &str) -> FrontmatterResult {
 
         // Count newlines in the frontmatter section (up to and including closing ---)
         let frontmatter_end_offset = 3 + close_idx + 4; // "---" + yaml + "\n---"
-        let mut frontmatter_lines = content[..frontmatter_end_offset]
-            .chars()
-            .filter(|&c| c == '\n')
-            .count();
+        let mut frontmatter_lines = leading_lines
+            + content[..frontmatter_end_offset]
+                .chars()
+                .filter(|&c| c == '\n')
+                .count();
 
         // Count and trim leading newlines from body (these are still "before" content)
         let leading_newlines = body_with_leading.chars().take_while(|&c| c == '\n').count();
@@ -45,22 +82,375 @@ pub fn parse(content: &str) -> FrontmatterResult {
         match serde_yaml::from_str(yaml_str) {
             Ok(map) => FrontmatterResult {
                 metadata: map,
+                // The YAML starts on the opening `---` line
+                positions: field_positions(yaml_str, leading_lines + 1),
+                error: None,
                 body: body.to_string(),
                 frontmatter_lines,
             },
-            Err(_) => FrontmatterResult {
-                metadata: HashMap::new(),
-                body: content.to_string(),
-                frontmatter_lines: 0,
-            },
+            Err(err) => {
+                // serde_yaml lines are 1-based from the opening `---` line
+                let (line, column) = err
+                    .location()
+                    .map_or((1, 1), |loc| (loc.line(), loc.column()));
+                FrontmatterResult {
+                    metadata: HashMap::new(),
+                    positions: HashMap::new(),
+                    error: Some(FrontmatterError {
+                        message: strip_locations(&err.to_string()),
+                        line: leading_lines + line,
+                        column,
+                    }),
+                    body: body.to_string(),
+                    frontmatter_lines,
+                }
+            }
         }
     } else {
         FrontmatterResult {
             metadata: HashMap::new(),
+            positions: HashMap::new(),
+            error: None,
             body: content.to_string(),
-            frontmatter_lines: 0,
+            frontmatter_lines: leading_lines,
+        }
+    }
+}
+
+/// Remove `at line N column M` fragments, which are relative to the YAML block.
+fn strip_locations(message: &str) -> String {
+    let mut result = String::new();
+    let mut rest = message;
+    while let Some(start) = rest.find(" at line ") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + " at line ".len()..];
+        let after = after.trim_start_matches(|c: char| c.is_ascii_digit());
+        let after = after.strip_prefix(" column ").unwrap_or(after);
+        rest = after.trim_start_matches(|c: char| c.is_ascii_digit());
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Find the line and column of each top-level key in a YAML block.
+///
+/// `first_line` is the file line number of the block's first line.
+fn field_positions(yaml: &str, first_line: usize) -> HashMap<String, FieldPosition> {
+    let mut positions = HashMap::new();
+    for (i, line) in yaml.lines().enumerate() {
+        let Some((key, value)) = top_level_key(line) else {
+            continue;
+        };
+
+        let value = value.trim_start();
+        let column = if value.is_empty() || value.starts_with('#') {
+            1
+        } else {
+            line.chars().count() - value.chars().count() + 1
+        };
+        positions.entry(key.to_string()).or_insert(FieldPosition {
+            line: first_line + i,
+            column,
+        });
+    }
+    positions
+}
+
+/// Split a `key: value` line into its key and the text after the colon.
+///
+/// Only top-level keys count: they start in the first column, and comments
+/// and list items are skipped.
+fn top_level_key(line: &str) -> Option<(&str, &str)> {
+    if line.starts_with(|c: char| c.is_whitespace() || c == '#' || c == '-') {
+        return None;
+    }
+    let (key, rest) = line.split_once(':')?;
+    let key = key.trim().trim_matches(|c| c == '"' || c == '\'');
+    (!key.is_empty()).then_some((key, rest))
+}
+
+/// Set a top-level frontmatter field, leaving the rest of the file untouched.
+///
+/// `value` is YAML as typed on the command line: `done`, `3`, `[a, b]`.
+/// Text that YAML would read differently (e.g. `a: b` or `x # y`) is quoted.
+/// An existing field keeps its position, its trailing comment and, for lists,
+/// its block or flow style. A new field is appended to the frontmatter, which
+/// is created if the file has none.
+pub fn set_field(content: &str, key: &str, value: &str) -> Result<String> {
+    check_key(key)?;
+    let (text, expected) = yaml_value(value);
+
+    let updated = match Block::find(content)? {
+        None => {
+            let newline = newline(content);
+            format!(
+                "---{nl}{}{nl}---{nl}{nl}{}",
+                entry(key, &text),
+                content,
+                nl = newline
+            )
+        }
+        Some(block) => match block.field(content, key) {
+            Some(field) => {
+                let replacement = field.replace(content, key, &text, &expected);
+                splice(content, field.range, &replacement)
+            }
+            None => {
+                let line = format!("{}{}", entry(key, &text), newline(content));
+                splice(content, block.end..block.end, &line)
+            }
+        },
+    };
+
+    // Never write a file whose frontmatter no longer means what was asked for
+    let check = parse(&updated);
+    if check.error.is_some() || check.metadata.get(key) != Some(&expected) {
+        bail!(
+            "Can't set '{}' to {}: the frontmatter would not be valid",
+            key,
+            value
+        );
+    }
+    Ok(updated)
+}
+
+/// Remove a top-level frontmatter field. Returns `None` if it isn't set.
+pub fn remove_field(content: &str, key: &str) -> Result<Option<String>> {
+    check_key(key)?;
+    let Some(block) = Block::find(content)? else {
+        return Ok(None);
+    };
+    Ok(block
+        .field(content, key)
+        .map(|field| splice(content, field.range, "")))
+}
+
+/// Reject keys that can't be written as a plain top-level YAML key.
+fn check_key(key: &str) -> Result<()> {
+    let valid = !key.is_empty()
+        && !key.starts_with(['#', '-', '"', '\''])
+        && !key.contains(|c: char| c == ':' || c.is_whitespace());
+    if !valid {
+        bail!("Invalid field name: '{}'", key);
+    }
+    Ok(())
+}
+
+/// YAML text to write for a command-line value, and the value it should parse to.
+fn yaml_value(raw: &str) -> (String, Value) {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return (String::new(), Value::Null);
+    }
+    match serde_yaml::from_str::<Value>(raw) {
+        // Plain strings must read back exactly, or comments/quotes were involved
+        Ok(Value::String(s)) if s != raw => quoted(raw),
+        Ok(Value::Mapping(_) | Value::Tagged(_)) | Err(_) => quoted(raw),
+        Ok(_) if raw.contains('\n') => quoted(raw),
+        Ok(value) => (raw.to_string(), value),
+    }
+}
+
+/// A string value in the YAML quoting serde_yaml would choose.
+fn quoted(raw: &str) -> (String, Value) {
+    let value = Value::String(raw.to_string());
+    let text = serde_yaml::to_string(&value).unwrap_or_default();
+    (text.trim_end().to_string(), value)
+}
+
+/// A `key: value` line (without the newline).
+fn entry(key: &str, text: &str) -> String {
+    if text.is_empty() {
+        format!("{}:", key)
+    } else {
+        format!("{}: {}", key, text)
+    }
+}
+
+/// The file's line ending.
+fn newline(content: &str) -> &'static str {
+    if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+fn splice(content: &str, range: Range<usize>, replacement: &str) -> String {
+    format!(
+        "{}{}{}",
+        &content[..range.start],
+        replacement,
+        &content[range.end..]
+    )
+}
+
+/// Byte offsets of the frontmatter YAML in a file.
+struct Block {
+    /// Start of the first line after the opening `---`.
+    start: usize,
+    /// Start of the closing `---` line.
+    end: usize,
+}
+
+impl Block {
+    /// Locate the frontmatter block, refusing to edit one that doesn't parse.
+    fn find(content: &str) -> Result<Option<Self>> {
+        if let Some(error) = parse(content).error {
+            bail!(
+                "Invalid frontmatter (line {}, col {}): {}",
+                error.line,
+                error.column,
+                error.message
+            );
+        }
+
+        let open = content.len() - content.trim_start().len();
+        if !content[open..].starts_with("---") {
+            return Ok(None);
+        }
+        let Some(open_end) = content[open..].find('\n').map(|i| open + i + 1) else {
+            return Ok(None);
+        };
+        // `parse` looks for "\n---" after the opening marker, so an empty block
+        // closes right after the opening line
+        let Some(close) = content[open + 3..].find("\n---").map(|i| open + 3 + i + 1) else {
+            return Ok(None);
+        };
+        Ok(Some(Block {
+            start: open_end.min(close),
+            end: close,
+        }))
+    }
+
+    /// Find the lines belonging to a top-level field.
+    fn field(&self, content: &str, key: &str) -> Option<Field> {
+        let lines = line_ranges(content, self.start..self.end);
+        let index = lines
+            .iter()
+            .position(|r| top_level_key(&content[r.clone()]).is_some_and(|(k, _)| k == key))?;
+
+        // Continuation lines are indented, or block list items at column 0.
+        // Blank lines only count if more of the value follows them.
+        let mut end = index + 1;
+        for (i, range) in lines.iter().enumerate().skip(index + 1) {
+            let line = &content[range.clone()];
+            if line.trim().is_empty() {
+                continue;
+            }
+            if line.starts_with([' ', '\t', '-']) {
+                end = i + 1;
+            } else {
+                break;
+            }
+        }
+
+        Some(Field {
+            range: lines[index].start..lines[end - 1].end,
+            lines: lines[index..end].to_vec(),
+        })
+    }
+}
+
+/// Byte ranges of the lines in a region, each including its line ending.
+fn line_ranges(content: &str, region: Range<usize>) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = region.start;
+    while start < region.end {
+        let end = content[start..region.end]
+            .find('\n')
+            .map_or(region.end, |i| start + i + 1);
+        ranges.push(start..end);
+        start = end;
+    }
+    ranges
+}
+
+/// The lines of one top-level field.
+struct Field {
+    range: Range<usize>,
+    lines: Vec<Range<usize>>,
+}
+
+impl Field {
+    /// Render the field with a new value, in the style of the old one.
+    fn replace(&self, content: &str, key: &str, text: &str, value: &Value) -> String {
+        let first = &content[self.lines[0].clone()];
+        let (line, line_ending) = match first.strip_suffix("\r\n") {
+            Some(line) => (line, "\r\n"),
+            None => (first.strip_suffix('\n').unwrap_or(first), "\n"),
+        };
+        let (_, old_value) = top_level_key(line).unwrap_or_default();
+        let comment = trailing_comment(old_value);
+
+        // Keep block lists as block lists, with the same indentation
+        let item_prefix = self.lines.get(1).and_then(|r| {
+            let item = &content[r.clone()];
+            let indent = item.len() - item.trim_start().len();
+            item.trim_start()
+                .starts_with("- ")
+                .then(|| format!("{}- ", &item[..indent]))
+        });
+        if let (Some(prefix), Value::Sequence(items)) = (item_prefix, value) {
+            let rendered: Option<Vec<String>> = items.iter().map(item_text).collect();
+            if let Some(rendered) = rendered {
+                let mut result = format!("{}:{}{}", key, comment, line_ending);
+                for item in rendered {
+                    result.push_str(&format!("{}{}{}", prefix, item, line_ending));
+                }
+                return result;
+            }
+        }
+
+        // Keep the original `key:` spelling (e.g. quoted keys) and spacing
+        let colon = line.find(':').map_or(0, |i| i + 1);
+        let spacing = if text.is_empty() {
+            ""
+        } else {
+            let after = &line[colon..];
+            let spaces = &after[..after.len() - after.trim_start().len()];
+            if spaces.is_empty() || spaces.contains('\t') {
+                " "
+            } else {
+                spaces
+            }
+        };
+        format!(
+            "{}{}{}{}{}",
+            &line[..colon],
+            spacing,
+            text,
+            comment,
+            line_ending
+        )
+    }
+}
+
+/// A list item as plain YAML, if it is a scalar.
+fn item_text(item: &Value) -> Option<String> {
+    match item {
+        Value::String(_) | Value::Number(_) | Value::Bool(_) => serde_yaml::to_string(item)
+            .ok()
+            .map(|s| s.trim_end().to_string()),
+        _ => None,
+    }
+}
+
+/// The ` # comment` at the end of a value, including its leading whitespace.
+fn trailing_comment(value: &str) -> &str {
+    let mut quote = None;
+    for (i, c) in value.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '#') if i == 0 || value[..i].ends_with([' ', '\t']) => {
+                let start = value[..i].trim_end().len();
+                return &value[start..];
+            }
+            _ => {}
         }
     }
+    ""
 }
 
 /// Parse a YAML value that might be an array of strings.
@@ -95,6 +485,56 @@ mod tests {
         assert_eq!(result.frontmatter_lines, 7);
     }
 
+    #[test]
+    fn parse_field_positions() {
+        let content =
+            "\n---\nstatus: active\ntags:\n  - a\n# note\n\"due\": 2026-03-01\n---\n\n# Title";
+        let result = parse(content);
+        let position = |key: &str| {
+            let p = result.positions[key];
+            (p.line, p.column)
+        };
+        assert_eq!(position("status"), (3, 9));
+        assert_eq!(position("tags"), (4, 1));
+        assert_eq!(position("due"), (7, 8));
+        assert_eq!(result.positions.len(), 3);
+    }
+
+    #[test]
+    fn parse_invalid_frontmatter() {
+        let content = "\n---\nstatus: active\ntags: [a\n---\n\n# Title\n\nBody";
+        let result = parse(content);
+
+        assert!(result.metadata.is_empty());
+        assert_eq!(result.body, "# Title\n\nBody");
+        assert_eq!(result.frontmatter_lines, 6);
+        assert_eq!(
+            result.error,
+            Some(FrontmatterError {
+                message: "did not find expected ',' or ']', while parsing a flow sequence"
+                    .to_string(),
+                line: 5,
+                column: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_non_mapping_frontmatter() {
+        let result = parse("---\n- a\n- b\n---\n# Title");
+        let error = result.error.expect("a list is not valid frontmatter");
+        assert_eq!(error.message, "invalid type: sequence, expected a map");
+        assert_eq!((error.line, error.column), (2, 1));
+    }
+
+    #[test]
+    fn parse_empty_frontmatter() {
+        let result = parse("---\n---\n\n# Title");
+        assert!(result.error.is_none());
+        assert!(result.metadata.is_empty());
+        assert_eq!(result.body, "# Title");
+    }
+
     #[test]
     fn parse_no_frontmatter() {
         let content = "Just some content";
@@ -120,4 +560,93 @@ mod tests {
         let result = parse_string_array(Some(&val));
         assert_eq!(result, vec!["a", "b", "c"]);
     }
+
+    #[test]
+    fn set_field_preserves_layout() {
+        let content = "---\n# Project metadata\nstatus: active  # current state\ntags:\n  - a\n  - b\npriority: 2\n---\n\n# Title\n";
+        let updated = set_field(content, "status", "done").unwrap();
+        assert_eq!(
+            updated,
+            "---\n# Project metadata\nstatus: done  # current state\ntags:\n  - a\n  - b\npriority: 2\n---\n\n# Title\n"
+        );
+    }
+
+    #[test]
+    fn set_field_keeps_list_style() {
+        let block = "---\ntags:\n- a\n- b\nstatus: x\n---\n";
+        assert_eq!(
+            set_field(block, "tags", "[c, d]").unwrap(),
+            "---\ntags:\n- c\n- d\nstatus: x\n---\n"
+        );
+
+        let flow = "---\ntags: [a, b]\n---\n";
+        assert_eq!(
+            set_field(flow, "tags", "[c, d]").unwrap(),
+            "---\ntags: [c, d]\n---\n"
+        );
+    }
+
+    #[test]
+    fn set_field_appends_new_fields() {
+        let content = "---\nstatus: active\n---\n\n# Title\n";
+        assert_eq!(
+            set_field(content, "due", "2026-03-01").unwrap(),
+            "---\nstatus: active\ndue: 2026-03-01\n---\n\n# Title\n"
+        );
+
+        let empty = "---\n---\n\n# Title\n";
+        assert_eq!(
+            set_field(empty, "status", "draft").unwrap(),
+            "---\nstatus: draft\n---\n\n# Title\n"
+        );
+    }
+
+    #[test]
+    fn set_field_creates_frontmatter() {
+        assert_eq!(
+            set_field("# Title\n", "status", "draft").unwrap(),
+            "---\nstatus: draft\n---\n\n# Title\n"
+        );
+    }
+
+    #[test]
+    fn set_field_quotes_ambiguous_values() {
+        let content = "---\n---\n";
+        let updated = set_field(content, "summary", "Fix: crash #12").unwrap();
+        assert_eq!(
+            parse(&updated).metadata["summary"],
+            Value::String("Fix: crash #12".to_string())
+        );
+
+        let updated = set_field(content, "estimate", "3").unwrap();
+        assert_eq!(updated, "---\nestimate: 3\n---\n");
+    }
+
+    #[test]
+    fn set_field_rejects_bad_input() {
+        assert!(set_field("---\n---\n", "bad key", "x").is_err());
+        assert!(set_field("---\n---\n", "", "x").is_err());
+        assert!(set_field("---\ntags: [a\n---\n", "status", "x").is_err());
+    }
+
+    #[test]
+    fn remove_field_removes_whole_value() {
+        let content =
+            "---\nstatus: active\ntags:\n  - a\n\n  - b\n\n# comment\ndue: 2026-03-01\n---\n";
+        assert_eq!(
+            remove_field(content, "tags").unwrap(),
+            Some("---\nstatus: active\n\n# comment\ndue: 2026-03-01\n---\n".to_string())
+        );
+        assert_eq!(remove_field(content, "missing").unwrap(), None);
+        assert_eq!(remove_field("# No frontmatter", "status").unwrap(), None);
+    }
+
+    #[test]
+    fn set_field_preserves_crlf() {
+        let content = "---\r\nstatus: a\r\n---\r\n";
+        assert_eq!(
+            set_field(content, "status", "b").unwrap(),
+            "---\r\nstatus: b\r\n---\r\n"
+        );
+    }
 }
@@ -0,0 +1,294 @@
+//! Link graph.
+//!
+//! Nodes are topics and edges are links between them, resolved once so that
+//! backlinks, exports and graph analysis all agree on what links where.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+use std::path::Path;
+
+use super::links::LinkResolver;
+use super::topic::Topic;
+
+/// A resolved link from one topic to another.
+#[derive(Debug, Clone, Copy)]
+pub struct Edge {
+    /// Index of the linking topic.
+    pub source: usize,
+    /// Index of the linked topic.
+    pub target: usize,
+    /// Index of the link in the source topic's `links`.
+    pub link: usize,
+}
+
+/// Links between topics, with nodes identified by their index in `topics`.
+pub struct LinkGraph<'a> {
+    pub topics: &'a [Topic],
+    /// Every resolved link, in topic and document order.
+    pub edges: Vec<Edge>,
+    outgoing: Vec<Vec<usize>>,
+    incoming: Vec<Vec<usize>>,
+    resolver: LinkResolver<'a>,
+}
+
+impl<'a> LinkGraph<'a> {
+    /// Resolve every link in every topic. Unresolved links and links from a
+    /// topic to itself are not edges.
+    pub fn build(topics: &'a [Topic]) -> Self {
+        let mut graph = LinkGraph {
+            topics,
+            edges: Vec::new(),
+            outgoing: vec![Vec::new(); topics.len()],
+            incoming: vec![Vec::new(); topics.len()],
+            resolver: LinkResolver::new(topics),
+        };
+
+        for (source, topic) in topics.iter().enumerate() {
+            for (link_index, link) in topic.links.iter().enumerate() {
+                let Some(target) = graph.resolver.resolve(topic, link) else {
+                    continue;
+                };
+                if target == source {
+                    continue;
+                }
+                graph.outgoing[source].push(graph.edges.len());
+                graph.incoming[target].push(graph.edges.len());
+                graph.edges.push(Edge {
+                    source,
+                    target,
+                    link: link_index,
+                });
+            }
+        }
+
+        graph
+    }
+
+    /// Find the node for a topic's path.
+    pub fn node(&self, path: &Path) -> Option<usize> {
+        self.resolver.index_of(path)
+    }
+
+    /// Links from a topic.
+    pub fn outgoing(&self, node: usize) -> impl Iterator<Item = &Edge> {
+        self.outgoing[node].iter().map(|&e| &self.edges[e])
+    }
+
+    /// Links to a topic.
+    pub fn incoming(&self, node: usize) -> impl Iterator<Item = &Edge> {
+        self.incoming[node].iter().map(|&e| &self.edges[e])
+    }
+
+    /// Topics within `depth` links of `start`, following links in either direction.
+    pub fn neighbourhood(&self, start: usize, depth: usize) -> BTreeSet<usize> {
+        let mut seen = BTreeSet::from([start]);
+        let mut queue = VecDeque::from([(start, 0)]);
+
+        while let Some((node, distance)) = queue.pop_front() {
+            if distance == depth {
+                continue;
+            }
+            let neighbours = self
+                .outgoing(node)
+                .map(|e| e.target)
+                .chain(self.incoming(node).map(|e| e.source));
+            for next in neighbours {
+                if seen.insert(next) {
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        seen
+    }
+
+    /// Number of distinct topics linking to a topic.
+    pub fn linked_from(&self, node: usize) -> usize {
+        self.incoming(node)
+            .map(|e| e.source)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// A topic with no links to or from other topics.
+    pub fn is_orphan(&self, node: usize) -> bool {
+        self.outgoing[node].is_empty() && self.incoming[node].is_empty()
+    }
+
+    /// A topic that other topics link to but that links nowhere itself.
+    /// Orphans are not dead ends.
+    pub fn is_dead_end(&self, node: usize) -> bool {
+        self.outgoing[node].is_empty() && !self.incoming[node].is_empty()
+    }
+
+    /// Connected components, ignoring link direction. Largest first.
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut seen = vec![false; self.topics.len()];
+        let mut components = Vec::new();
+        for start in 0..self.topics.len() {
+            if seen[start] {
+                continue;
+            }
+            let component: Vec<usize> = self.neighbourhood(start, usize::MAX).into_iter().collect();
+            for &node in &component {
+                seen[node] = true;
+            }
+            components.push(component);
+        }
+        components.sort_by_key(|c| std::cmp::Reverse(c.len()));
+        components
+    }
+
+    /// Groups of two or more topics that can all reach each other by
+    /// following links (strongly connected components). Largest first.
+    pub fn clusters(&self) -> Vec<Vec<usize>> {
+        // Kosaraju: order nodes by DFS finish time, then collect each
+        // component by walking links backwards in reverse finish order
+        let count = self.topics.len();
+        let mut visited = vec![false; count];
+        let mut order = Vec::with_capacity(count);
+        for start in 0..count {
+            if visited[start] {
+                continue;
+            }
+            visited[start] = true;
+            let mut stack = vec![(start, 0)];
+            while let Some((node, next)) = stack.last_mut() {
+                match self.outgoing[*node].get(*next) {
+                    Some(&edge) => {
+                        *next += 1;
+                        let target = self.edges[edge].target;
+                        if !visited[target] {
+                            visited[target] = true;
+                            stack.push((target, 0));
+                        }
+                    }
+                    None => {
+                        order.push(*node);
+                        stack.pop();
+                    }
+                }
+            }
+        }
+
+        let mut assigned = vec![false; count];
+        let mut clusters = Vec::new();
+        for &start in order.iter().rev() {
+            if assigned[start] {
+                continue;
+            }
+            assigned[start] = true;
+            let mut cluster = vec![start];
+            let mut stack = vec![start];
+            while let Some(node) = stack.pop() {
+                for edge in self.incoming(node) {
+                    if !assigned[edge.source] {
+                        assigned[edge.source] = true;
+                        cluster.push(edge.source);
+                        stack.push(edge.source);
+                    }
+                }
+            }
+            if cluster.len() > 1 {
+                cluster.sort_unstable();
+                clusters.push(cluster);
+            }
+        }
+        clusters.sort_by_key(|c| std::cmp::Reverse(c.len()));
+        clusters
+    }
+
+    /// Distinct `(source, target)` pairs between the given nodes, with how
+    /// many links each pair has, in node order.
+    pub fn connections(&self, nodes: &BTreeSet<usize>) -> Vec<(usize, usize, usize)> {
+        let mut pairs: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+        for edge in &self.edges {
+            if nodes.contains(&edge.source) && nodes.contains(&edge.target) {
+                *pairs.entry((edge.source, edge.target)).or_default() += 1;
+            }
+        }
+        pairs
+            .into_iter()
+            .map(|((source, target), count)| (source, target, count))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn topics() -> Vec<Topic> {
+        vec![
+            Topic::from_content(
+                Path::new("/notes/a.md"),
+                "# A\n\n[B](b.md) and [[b]] and [self](#a)",
+            ),
+            Topic::from_content(Path::new("/notes/b.md"), "# B\n\n[C](sub/c.md)"),
+            Topic::from_content(Path::new("/notes/sub/c.md"), "# C\n\n[missing](x.md)"),
+            Topic::from_content(Path::new("/notes/d.md"), "# D"),
+        ]
+    }
+
+    #[test]
+    fn resolves_edges() {
+        let topics = topics();
+        let graph = LinkGraph::build(&topics);
+        let edges: Vec<(usize, usize)> = graph.edges.iter().map(|e| (e.source, e.target)).collect();
+        assert_eq!(edges, vec![(0, 1), (0, 1), (1, 2)]);
+
+        assert_eq!(graph.incoming(1).count(), 2);
+        assert_eq!(graph.outgoing(2).count(), 0);
+    }
+
+    #[test]
+    fn neighbourhood_follows_both_directions() {
+        let topics = topics();
+        let graph = LinkGraph::build(&topics);
+        assert_eq!(graph.neighbourhood(2, 1), BTreeSet::from([1, 2]));
+        assert_eq!(graph.neighbourhood(2, 2), BTreeSet::from([0, 1, 2]));
+        assert_eq!(graph.neighbourhood(3, 5), BTreeSet::from([3]));
+    }
+
+    #[test]
+    fn classifies_orphans_and_dead_ends() {
+        let topics = topics();
+        let graph = LinkGraph::build(&topics);
+        assert!(graph.is_dead_end(2));
+        assert!(!graph.is_dead_end(0));
+        assert!(graph.is_orphan(3));
+        assert!(!graph.is_dead_end(3));
+        assert_eq!(graph.linked_from(1), 1);
+    }
+
+    #[test]
+    fn components_ignore_direction() {
+        let topics = topics();
+        let graph = LinkGraph::build(&topics);
+        assert_eq!(graph.components(), vec![vec![0, 1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn clusters_are_strongly_connected() {
+        let topics = vec![
+            Topic::from_content(Path::new("/n/a.md"), "# A\n\n[[b]]"),
+            Topic::from_content(Path::new("/n/b.md"), "# B\n\n[[c]] [[d]]"),
+            Topic::from_content(Path::new("/n/c.md"), "# C\n\n[[a]]"),
+            Topic::from_content(Path::new("/n/d.md"), "# D\n\n[[e]]"),
+            Topic::from_content(Path::new("/n/e.md"), "# E\n\n[[d]]"),
+            Topic::from_content(Path::new("/n/f.md"), "# F\n\n[[a]]"),
+        ];
+        let graph = LinkGraph::build(&topics);
+        assert_eq!(graph.clusters(), vec![vec![0, 1, 2], vec![3, 4]]);
+    }
+
+    #[test]
+    fn connections_merge_parallel_links() {
+        let topics = topics();
+        let graph = LinkGraph::build(&topics);
+        assert_eq!(
+            graph.connections(&BTreeSet::from([0, 1, 2, 3])),
+            vec![(0, 1, 2), (1, 2, 1)]
+        );
+        assert_eq!(graph.connections(&BTreeSet::from([0, 2])), vec![]);
+    }
+}
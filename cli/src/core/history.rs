@@ -0,0 +1,255 @@
+//! Undo history.
+//!
+//! Commands that change notes record each file's content before and after in
+//! `<root>/.hypha/history.json`, so `hypha undo` can put it back. Only the
+//! last [`HISTORY_LIMIT`] operations are kept.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::changes::{Change, ChangeSet};
+use crate::constants::{DATA_DIR, HISTORY_FILE};
+
+/// Number of operations kept in the history.
+const HISTORY_LIMIT: usize = 20;
+
+/// One file changed by an operation. `None` means the file didn't exist.
+#[derive(Debug, Serialize, Deserialize)]
+struct FileChange {
+    /// Path relative to the root.
+    path: PathBuf,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+/// A recorded operation.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Entry {
+    /// What was run, for display (e.g. `rename old "New Title"`).
+    pub command: String,
+    changes: Vec<FileChange>,
+}
+
+impl Entry {
+    /// The changes that revert this operation.
+    ///
+    /// Fails if any file has changed since, so later edits are never lost.
+    pub fn revert(&self, root: &Path) -> Result<ChangeSet> {
+        let mut changes = ChangeSet::new();
+        for change in self.changes.iter().rev() {
+            let path = root.join(&change.path);
+            let current = if path.exists() {
+                Some(
+                    fs::read_to_string(&path)
+                        .with_context(|| format!("Failed to read {}", path.display()))?,
+                )
+            } else {
+                None
+            };
+            if current != change.after {
+                bail!(
+                    "Can't undo \"{}\": {} has changed since",
+                    self.command,
+                    change.path.display()
+                );
+            }
+
+            match &change.before {
+                Some(before) => changes.write(&path, before.clone())?,
+                None => changes.remove(&path)?,
+            }
+        }
+        Ok(changes)
+    }
+}
+
+fn history_path(root: &Path) -> PathBuf {
+    root.join(DATA_DIR).join(HISTORY_FILE)
+}
+
+/// Load the history, oldest operation first.
+fn load(root: &Path) -> Result<Vec<Entry>> {
+    let path = history_path(root);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("Invalid undo history: {}", path.display()))
+}
+
+fn save(root: &Path, entries: &[Entry]) -> Result<()> {
+    let path = history_path(root);
+    let dir = path.parent().context("Invalid undo history path")?;
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    // Write to a temp file and rename, so the history is never half written
+    let content = serde_json::to_string(entries).context("Failed to serialize undo history")?;
+    let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+    fs::write(&tmp, content).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+/// Describe a command for the history, quoting arguments that contain spaces.
+pub fn describe(args: &[&str]) -> String {
+    args.iter()
+        .map(|arg| {
+            if arg.contains(char::is_whitespace) || arg.is_empty() {
+                format!("\"{}\"", arg)
+            } else {
+                arg.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Describe a command that takes a topic or `--where`, such as `set` or `move`.
+pub fn describe_targets(name: &str, filter: Option<&str>, args: &[String]) -> String {
+    let mut parts = vec![name];
+    if let Some(query) = filter {
+        parts.extend(["--where", query]);
+    }
+    parts.extend(args.iter().map(String::as_str));
+    describe(&parts)
+}
+
+/// Record applied changes as the latest operation.
+///
+/// An unreadable history is replaced, since the changes have already been made.
+pub fn record(root: &Path, command: &str, changes: &ChangeSet) -> Result<()> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    let mut entries = load(root).unwrap_or_default();
+    entries.push(Entry {
+        command: command.to_string(),
+        changes: changes
+            .changes()
+            .iter()
+            .map(|change| {
+                let (before, after) = match change {
+                    Change::Write { before, after, .. } => (before.clone(), Some(after.clone())),
+                    Change::Remove { before, .. } => (Some(before.clone()), None),
+                };
+                FileChange {
+                    path: change
+                        .path()
+                        .strip_prefix(root)
+                        .unwrap_or(change.path())
+                        .to_path_buf(),
+                    before,
+                    after,
+                }
+            })
+            .collect(),
+    });
+    let excess = entries.len().saturating_sub(HISTORY_LIMIT);
+    entries.drain(..excess);
+    save(root, &entries)
+}
+
+/// The most recent operation, if any.
+pub fn last(root: &Path) -> Result<Option<Entry>> {
+    Ok(load(root)?.pop())
+}
+
+/// Forget the most recent operation, once it has been undone.
+pub fn pop(root: &Path) -> Result<()> {
+    let mut entries = load(root)?;
+    entries.pop();
+    save(root, &entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn describes_commands() {
+        assert_eq!(
+            describe(&["rename", "old", "New Title"]),
+            "rename old \"New Title\""
+        );
+    }
+
+    #[test]
+    fn reverts_recorded_changes() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(root.join("old.md"), "# Old\n").unwrap();
+        fs::write(root.join("other.md"), "[Old](old.md)\n").unwrap();
+
+        let mut changes = ChangeSet::new();
+        changes
+            .write(&root.join("new.md"), "# New\n".to_string())
+            .unwrap();
+        changes.remove(&root.join("old.md")).unwrap();
+        changes
+            .write(&root.join("other.md"), "[New](new.md)\n".to_string())
+            .unwrap();
+        changes.apply().unwrap();
+        record(root, "rename Old -> New", &changes).unwrap();
+
+        let entry = last(root).unwrap().unwrap();
+        assert_eq!(entry.command, "rename Old -> New");
+        entry.revert(root).unwrap().apply().unwrap();
+        pop(root).unwrap();
+
+        assert!(!root.join("new.md").exists());
+        assert_eq!(fs::read_to_string(root.join("old.md")).unwrap(), "# Old\n");
+        assert_eq!(
+            fs::read_to_string(root.join("other.md")).unwrap(),
+            "[Old](old.md)\n"
+        );
+        assert!(last(root).unwrap().is_none());
+    }
+
+    #[test]
+    fn refuses_to_revert_edited_files() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(root.join("a.md"), "before\n").unwrap();
+
+        let mut changes = ChangeSet::new();
+        changes
+            .write(&root.join("a.md"), "after\n".to_string())
+            .unwrap();
+        changes.apply().unwrap();
+        record(root, "set a", &changes).unwrap();
+        fs::write(root.join("a.md"), "edited\n").unwrap();
+
+        let err = last(root)
+            .unwrap()
+            .unwrap()
+            .revert(root)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("a.md has changed since"), "{}", err);
+    }
+
+    #[test]
+    fn keeps_only_recent_operations() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+
+        for i in 0..HISTORY_LIMIT + 5 {
+            let mut changes = ChangeSet::new();
+            changes
+                .write(&root.join("a.md"), format!("{}\n", i))
+                .unwrap();
+            changes.apply().unwrap();
+            record(root, &format!("set {}", i), &changes).unwrap();
+        }
+
+        let entries = load(root).unwrap();
+        assert_eq!(entries.len(), HISTORY_LIMIT);
+        assert_eq!(entries[0].command, "set 5");
+    }
+}
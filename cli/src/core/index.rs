@@ -1,13 +1,18 @@
-use anyhow::Result;
-use std::collections::HashMap;
+use anyhow::{bail, Context, Result};
+use ignore::overrides::OverrideBuilder;
+use ignore::{Walk, WalkBuilder};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
-use walkdir::WalkDir;
+use std::path::{Path, PathBuf};
 
-use super::query::{self, Query};
+use super::cache::IndexCache;
+use super::query::{self, FieldValue, Query, Queryable};
+use super::rank;
+use super::settings::{IndexSettings, Settings};
 use super::topic::Topic;
 use crate::cli::SortOrder;
-use crate::constants::TEMPLATE_FILE;
+use crate::constants::{DATA_DIR, IGNORE_FILE, TEMPLATE_FILE};
 
 /// Statistics about the index.
 #[derive(Debug, Default)]
@@ -22,37 +27,76 @@ pub struct IndexStats {
 /// Sort topics according to the specified order.
 fn sort_topics(topics: &mut [Topic], sort: SortOrder) {
     match sort {
-        SortOrder::Alpha => {
-            topics.sort_by(|a, b| a.title.to_lowercase().cmp(&b.title.to_lowercase()));
+        // Relevance needs a query; `search` ranks results itself
+        SortOrder::Alpha | SortOrder::Relevance => {
+            topics.sort_by_key(|t| t.title.to_lowercase());
         }
         SortOrder::Modified => {
-            topics.sort_by(|a, b| {
-                let a_time = fs::metadata(&a.path).and_then(|m| m.modified()).ok();
-                let b_time = fs::metadata(&b.path).and_then(|m| m.modified()).ok();
-                // Most recent first
-                b_time.cmp(&a_time)
-            });
+            // Most recent first
+            topics.sort_by_key(|t| Reverse(t.modified));
         }
         SortOrder::Created => {
-            topics.sort_by(|a, b| {
-                let a_time = fs::metadata(&a.path).and_then(|m| m.created()).ok();
-                let b_time = fs::metadata(&b.path).and_then(|m| m.created()).ok();
-                // Most recent first
-                b_time.cmp(&a_time)
-            });
+            // Most recent first
+            topics.sort_by_key(|t| Reverse(t.created));
         }
     }
 }
 
+/// Create a directory walker that honours ignore files and configured globs.
+///
+/// Files are skipped if they match `.gitignore`, `.hyphaignore` (gitignore syntax)
+/// or an `exclude` glob, or if `include` globs are configured and none match.
+/// Symlinks are followed; the walker reports loops as errors, which [`build`]
+/// prints as warnings.
+fn walker(root: &Path, settings: &IndexSettings) -> Result<Walk> {
+    let mut overrides = OverrideBuilder::new(root);
+    for glob in &settings.include {
+        overrides
+            .add(glob)
+            .with_context(|| format!("Invalid include glob: {}", glob))?;
+    }
+    for glob in &settings.exclude {
+        overrides
+            .add(&format!("!{}", glob))
+            .with_context(|| format!("Invalid exclude glob: {}", glob))?;
+    }
+    let overrides = overrides.build().context("Invalid index globs")?;
+
+    let walk = WalkBuilder::new(root)
+        .hidden(false)
+        .follow_links(true)
+        .require_git(false)
+        .add_custom_ignore_filename(IGNORE_FILE)
+        .overrides(overrides)
+        // Skip Hypha's own data directory and git internals
+        .filter_entry(|e| {
+            let is_dir = e.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            !(is_dir && (e.file_name() == DATA_DIR || e.file_name() == ".git"))
+        })
+        .build();
+
+    Ok(walk)
+}
+
 /// Build an index of all topics in the root directory.
+///
+/// Unchanged files are served from the on-disk cache in `.hypha/`.
 pub fn build(root: &Path, sort: SortOrder) -> Result<Vec<Topic>> {
+    let settings = Settings::load(root)?;
     let mut topics = Vec::new();
+    let mut cache = IndexCache::load(root);
+    // Canonical paths already indexed, so symlinked directories don't duplicate topics
+    let mut visited = HashSet::new();
 
-    for entry in WalkDir::new(root)
-        .follow_links(true)
-        .into_iter()
-        .filter_map(|e| e.ok())
-    {
+    for entry in walker(root, &settings.index)? {
+        // Skip unreadable entries and symlink loops, but say so
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                eprintln!("Warning: {}", err);
+                continue;
+            }
+        };
         let path = entry.path();
 
         // Skip template file
@@ -66,12 +110,26 @@ pub fn build(root: &Path, sort: SortOrder) -> Result<Vec<Topic>> {
 
         // Only process .md files
         if path.extension().map(|e| e == "md").unwrap_or(false) {
-            if let Ok(topic) = Topic::from_path(path) {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+            if let Ok(canonical) = fs::canonicalize(path) {
+                if !visited.insert(canonical) {
+                    continue;
+                }
+            }
+            if let Ok(topic) = cache.topic(path, &metadata) {
                 topics.push(topic);
             }
         }
     }
 
+    // The cache is only an optimisation; a read-only root still works
+    let _ = cache.save();
+
     sort_topics(&mut topics, sort);
 
     Ok(topics)
@@ -85,12 +143,18 @@ pub fn list(root: &Path, sort: SortOrder) -> Result<Vec<Topic>> {
 /// Search topics by query string.
 pub fn search(root: &Path, query_str: &str, sort: SortOrder) -> Result<Vec<Topic>> {
     let topics = build(root, sort)?;
-    let query = Query::parse(query_str);
+    let query = Query::parse(query_str)?;
 
     if query.is_empty() {
         return Ok(topics);
     }
 
+    // Score against the whole index so term rarity reflects every topic
+    let scores = match sort {
+        SortOrder::Relevance => rank::scores(&topics, &query.terms()),
+        _ => HashMap::new(),
+    };
+
     let mut results: Vec<Topic> = topics
         .into_iter()
         .filter(|t| query::matches(t, &query))
@@ -98,10 +162,117 @@ pub fn search(root: &Path, query_str: &str, sort: SortOrder) -> Result<Vec<Topic
 
     // Re-sort filtered results
     sort_topics(&mut results, sort);
+    if !scores.is_empty() {
+        // Stable sort keeps alphabetical order among equal scores
+        let score = |t: &Topic| scores.get(&t.path).copied().unwrap_or(0.0);
+        results.sort_by(|a, b| score(b).total_cmp(&score(a)));
+    }
 
     Ok(results)
 }
 
+/// Find the file for a command that acts on exactly one topic.
+///
+/// Accepts a path relative to the root (with or without `.md`), a file stem
+/// or a title, all case-insensitive. Exact stems and titles win over titles
+/// containing every word of the query. A query with filters or operators
+/// (`status:active`, `a OR b`) is matched with the query language instead.
+/// Either way free-text terms only match titles, so a topic that only
+/// mentions the query in its body is never picked.
+pub fn find_one(root: &Path, query: &str) -> Result<PathBuf> {
+    // First try exact path match
+    let direct_path = root.join(query);
+    if direct_path.is_file() {
+        return Ok(direct_path);
+    }
+
+    // Try with .md extension
+    let with_ext = root.join(format!("{}.md", query));
+    if with_ext.is_file() {
+        return Ok(with_ext);
+    }
+
+    let topics = build(root, SortOrder::Alpha)?;
+    let wanted = query.trim().to_lowercase();
+    let stem = |t: &Topic| {
+        t.path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_lowercase())
+            .unwrap_or_default()
+    };
+    let mut matches: Vec<&Topic> = topics
+        .iter()
+        .filter(|t| stem(t) == wanted || t.title.to_lowercase() == wanted)
+        .collect();
+    if matches.is_empty() {
+        matches = match Query::parse(query) {
+            Ok(parsed) if !parsed.is_plain() => topics
+                .iter()
+                .filter(|t| query::matches(&ByTitle(t), &parsed))
+                .collect(),
+            _ => {
+                let words: Vec<&str> = wanted.split_whitespace().collect();
+                topics
+                    .iter()
+                    .filter(|t| {
+                        let title = t.title.to_lowercase();
+                        !words.is_empty() && words.iter().all(|word| title.contains(word))
+                    })
+                    .collect()
+            }
+        };
+    }
+
+    match matches.as_slice() {
+        [] => bail!("No topic found matching: {}", query),
+        [topic] => Ok(topic.path.clone()),
+        _ => {
+            let matches: Vec<String> = matches
+                .iter()
+                .enumerate()
+                .map(|(i, topic)| {
+                    format!("  {}: {} ({})", i + 1, topic.title, topic.path.display())
+                })
+                .collect();
+            bail!(
+                "Multiple matches found:\n{}\nPlease be more specific",
+                matches.join("\n")
+            )
+        }
+    }
+}
+
+/// A topic whose free-text search covers only its title.
+struct ByTitle<'a>(&'a Topic);
+
+impl Queryable for ByTitle<'_> {
+    fn get_field(&self, key: &str) -> Option<FieldValue> {
+        self.0.get_field(key)
+    }
+
+    fn searchable_text(&self) -> String {
+        self.0.title.clone()
+    }
+}
+
+/// Resolve the topics a command acts on, from `--where` or its first
+/// argument, and return them with the remaining arguments.
+pub fn targets<'a>(
+    root: &Path,
+    args: &'a [String],
+    filter: Option<&str>,
+) -> Result<(Vec<PathBuf>, &'a [String])> {
+    if let Some(query) = filter {
+        let topics = search(root, query, SortOrder::Alpha)?;
+        return Ok((topics.into_iter().map(|t| t.path).collect(), args));
+    }
+
+    match args.split_first() {
+        Some((topic, rest)) => Ok((vec![find_one(root, topic)?], rest)),
+        None => bail!("Expected a topic, or --where with a query"),
+    }
+}
+
 /// Get statistics about the index.
 pub fn stats(root: &Path) -> Result<IndexStats> {
     let topics = build(root, SortOrder::Alpha)?;
@@ -0,0 +1,345 @@
+//! Link resolution.
+//!
+//! Maps link targets found in topics back to the topics they point at.
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use super::markdown::{LinkKind, ParsedLink};
+use super::topic::Topic;
+
+/// Resolves links from any topic to indices into a list of topics.
+///
+/// Paths, file stems, titles and trailing path components are indexed once,
+/// so each link is a map lookup rather than a scan of every topic.
+pub struct LinkResolver<'a> {
+    topics: &'a [Topic],
+    by_path: HashMap<PathBuf, usize>,
+    /// Lowercase file stem to every topic with that stem, in index order.
+    by_stem: HashMap<String, Vec<usize>>,
+    /// Lowercase title to the first topic with that title.
+    by_title: HashMap<String, usize>,
+    /// Lowercase trailing path components without the extension
+    /// (`projects/plan`) to the first topic whose path ends with them.
+    by_suffix: HashMap<String, usize>,
+}
+
+impl<'a> LinkResolver<'a> {
+    pub fn new(topics: &'a [Topic]) -> Self {
+        let mut resolver = LinkResolver {
+            topics,
+            by_path: HashMap::new(),
+            by_stem: HashMap::new(),
+            by_title: HashMap::new(),
+            by_suffix: HashMap::new(),
+        };
+        for (i, topic) in topics.iter().enumerate() {
+            resolver.by_path.insert(normalize(&topic.path), i);
+            if let Some(stem) = topic.path.file_stem() {
+                let stem = stem.to_string_lossy().to_lowercase();
+                resolver.by_stem.entry(stem).or_default().push(i);
+            }
+            resolver
+                .by_title
+                .entry(topic.title.to_lowercase())
+                .or_insert(i);
+
+            let without_ext = topic.path.with_extension("");
+            let mut suffix = String::new();
+            for component in without_ext.components().rev() {
+                let Component::Normal(name) = component else {
+                    break;
+                };
+                let name = name.to_string_lossy().to_lowercase();
+                suffix = if suffix.is_empty() {
+                    name
+                } else {
+                    format!("{}/{}", name, suffix)
+                };
+                resolver.by_suffix.entry(suffix.clone()).or_insert(i);
+            }
+        }
+        resolver
+    }
+
+    /// Find the topic a link in `source` points at.
+    ///
+    /// Markdown links are resolved relative to the source's directory, with
+    /// `.md` implied if the target has no extension; wiki links use
+    /// [`LinkResolver::resolve_wiki`]. Links to headings in the source itself
+    /// resolve to nothing.
+    pub fn resolve(&self, source: &Topic, link: &ParsedLink) -> Option<usize> {
+        if link.path().is_empty() {
+            return None;
+        }
+
+        if link.kind == LinkKind::Wiki {
+            return self.wiki(link.path());
+        }
+
+        let path = normalize(&source.path.parent()?.join(link.path()));
+        self.by_path.get(&path).copied().or_else(|| {
+            if path.extension().is_some() {
+                return None;
+            }
+            self.by_path.get(&path.with_extension("md")).copied()
+        })
+    }
+
+    /// The index of the topic at `path`, if it's indexed.
+    pub fn index_of(&self, path: &Path) -> Option<usize> {
+        self.by_path.get(&normalize(path)).copied()
+    }
+
+    /// The indexed topic at `path`, if any.
+    pub fn topic_at(&self, path: &Path) -> Option<&'a Topic> {
+        self.index_of(path).map(|i| &self.topics[i])
+    }
+
+    /// Topics other than `target` with at least one link to it, in index
+    /// order.
+    pub fn linking_to(&self, target: usize) -> Vec<usize> {
+        self.topics
+            .iter()
+            .enumerate()
+            .filter(|&(source, topic)| {
+                source != target
+                    && topic
+                        .links
+                        .iter()
+                        .any(|link| self.resolve(topic, link) == Some(target))
+            })
+            .map(|(source, _)| source)
+            .collect()
+    }
+
+    /// Resolve a wiki-link target to a topic anywhere in the index.
+    ///
+    /// The target may be a file stem (`topic-b`), a title (`Topic B`), or a
+    /// path relative to any directory (`projects/topic-b`). Any `#heading`
+    /// suffix and `.md` extension are ignored. Matching is case-insensitive;
+    /// path and stem matches take precedence over titles.
+    pub fn resolve_wiki(&self, target: &str) -> Option<&'a Topic> {
+        self.wiki(target).map(|i| &self.topics[i])
+    }
+
+    /// Every topic a bare wiki-link target (no `/`) names by file stem.
+    ///
+    /// More than one means the link is ambiguous: [`LinkResolver::resolve_wiki`]
+    /// picks the first, which may not be the one intended.
+    pub fn stem_matches(&self, target: &str) -> Vec<&'a Topic> {
+        match wiki_key(target) {
+            Some(target) if !target.contains('/') => self
+                .by_stem
+                .get(&target)
+                .map(|matches| matches.iter().map(|&i| &self.topics[i]).collect())
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn wiki(&self, target: &str) -> Option<usize> {
+        let target = wiki_key(target)?;
+        if target.contains('/') {
+            // Compare trailing path components, e.g. `projects/topic-b`
+            let wanted: Vec<&str> = target.split('/').filter(|c| !c.is_empty()).collect();
+            return self.by_suffix.get(&wanted.join("/")).copied();
+        }
+        self.by_stem
+            .get(&target)
+            .and_then(|matches| matches.first())
+            .or_else(|| self.by_title.get(&target))
+            .copied()
+    }
+}
+
+/// The lowercase lookup key for a wiki-link target, without any `#heading`
+/// suffix or `.md` extension.
+fn wiki_key(target: &str) -> Option<String> {
+    let target = target.split('#').next().unwrap_or(target).trim();
+    let target = target.strip_suffix(".md").unwrap_or(target);
+    (!target.is_empty()).then(|| target.to_lowercase())
+}
+
+/// Lexically remove `.` and `..` components so joined paths compare equal.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Markdown link target for `target` as written in a file at `source`.
+///
+/// Relative to the source's directory with `/` separators. Targets containing
+/// spaces or parentheses are wrapped in `<...>` so they stay one destination.
+pub fn relative_href(source: &Path, target: &Path) -> String {
+    let from = normalize(source.parent().unwrap_or(Path::new("")));
+    let to = normalize(target);
+    let from: Vec<Component> = from.components().collect();
+    let to: Vec<Component> = to.components().collect();
+
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let parts: Vec<String> = std::iter::repeat_n("..".to_string(), from.len() - common)
+        .chain(
+            to[common..]
+                .iter()
+                .map(|c| c.as_os_str().to_string_lossy().to_string()),
+        )
+        .collect();
+
+    let href = parts.join("/");
+    if href.contains([' ', '(', ')']) {
+        format!("<{}>", href)
+    } else {
+        href
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn topics() -> Vec<Topic> {
+        vec![
+            Topic::from_content(Path::new("/notes/topic-b.md"), "# Topic B"),
+            Topic::from_content(Path::new("/notes/projects/plan.md"), "# Launch Plan"),
+            Topic::from_content(Path::new("/notes/areas/plan.md"), "# Area Plan"),
+        ]
+    }
+
+    fn resolved(target: &str) -> Option<String> {
+        let topics = topics();
+        LinkResolver::new(&topics)
+            .resolve_wiki(target)
+            .map(|t| t.path.display().to_string())
+    }
+
+    #[test]
+    fn resolves_by_stem() {
+        assert_eq!(resolved("topic-b"), Some("/notes/topic-b.md".to_string()));
+        assert_eq!(
+            resolved("Topic-B.md"),
+            Some("/notes/topic-b.md".to_string())
+        );
+    }
+
+    #[test]
+    fn resolves_by_title() {
+        assert_eq!(resolved("topic b"), Some("/notes/topic-b.md".to_string()));
+        assert_eq!(
+            resolved("Launch Plan#Goals"),
+            Some("/notes/projects/plan.md".to_string())
+        );
+    }
+
+    #[test]
+    fn resolves_by_partial_path() {
+        assert_eq!(
+            resolved("areas/plan"),
+            Some("/notes/areas/plan.md".to_string())
+        );
+        assert_eq!(resolved("other/plan"), None);
+        assert_eq!(
+            resolved("notes/Topic-B"),
+            Some("/notes/topic-b.md".to_string())
+        );
+    }
+
+    fn link(kind: LinkKind, target: &str) -> ParsedLink {
+        ParsedLink {
+            target: target.to_string(),
+            kind,
+            alias: None,
+            text: String::new(),
+            context: String::new(),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    #[test]
+    fn resolver_follows_relative_paths() {
+        let topics = topics();
+        let resolver = LinkResolver::new(&topics);
+        let source = &topics[1]; // /notes/projects/plan.md
+
+        let resolved = |kind, target| resolver.resolve(source, &link(kind, target));
+        assert_eq!(resolved(LinkKind::Markdown, "../areas/plan.md"), Some(2));
+        assert_eq!(resolved(LinkKind::Markdown, "./../topic-b"), Some(0));
+        assert_eq!(resolved(LinkKind::Markdown, "plan.md#goals"), Some(1));
+        assert_eq!(resolved(LinkKind::Markdown, "topic-b.md"), None);
+        assert_eq!(resolved(LinkKind::Markdown, "#goals"), None);
+        assert_eq!(resolved(LinkKind::Wiki, "Topic B"), Some(0));
+    }
+
+    #[test]
+    fn finds_topics_linking_to_a_target() {
+        let topics = vec![
+            Topic::from_content(Path::new("/notes/a.md"), "# A\n\n[[c]] [self](a.md)"),
+            Topic::from_content(Path::new("/notes/b.md"), "# B\n\n[c](c.md#top)"),
+            Topic::from_content(Path::new("/notes/c.md"), "# C\n\n[[a]]"),
+        ];
+        let resolver = LinkResolver::new(&topics);
+        assert_eq!(resolver.linking_to(2), vec![0, 1]);
+        assert_eq!(resolver.linking_to(0), vec![2]);
+        assert!(resolver.linking_to(1).is_empty());
+    }
+
+    #[test]
+    fn stem_matches_find_ambiguous_targets() {
+        let topics = topics();
+        let resolver = LinkResolver::new(&topics);
+        let paths = |target| -> Vec<String> {
+            resolver
+                .stem_matches(target)
+                .iter()
+                .map(|t| t.path.display().to_string())
+                .collect()
+        };
+        assert_eq!(
+            paths("Plan#Goals"),
+            vec!["/notes/projects/plan.md", "/notes/areas/plan.md"]
+        );
+        assert_eq!(paths("topic-b.md"), vec!["/notes/topic-b.md"]);
+        assert!(paths("areas/plan").is_empty());
+        assert!(paths("Launch Plan").is_empty());
+    }
+
+    #[test]
+    fn normalize_removes_dot_components() {
+        assert_eq!(
+            normalize(Path::new("/notes/a/./../b/c.md")),
+            PathBuf::from("/notes/b/c.md")
+        );
+    }
+
+    #[test]
+    fn unresolved_targets() {
+        assert_eq!(resolved("missing"), None);
+        assert_eq!(resolved("#heading"), None);
+    }
+
+    #[test]
+    fn relative_hrefs() {
+        let href = |from: &str, to: &str| relative_href(Path::new(from), Path::new(to));
+        assert_eq!(href("/notes/a.md", "/notes/b.md"), "b.md");
+        assert_eq!(
+            href("/notes/a.md", "/notes/projects/plan.md"),
+            "projects/plan.md"
+        );
+        assert_eq!(
+            href("/notes/areas/a.md", "/notes/projects/plan.md"),
+            "../projects/plan.md"
+        );
+        assert_eq!(href("/notes/x/y/a.md", "/notes/b.md"), "../../b.md");
+        assert_eq!(href("/notes/a.md", "/notes/My Plan.md"), "<My Plan.md>");
+    }
+}
@@ -1,21 +1,133 @@
 //! Markdown body parsing.
 //!
-//! Parses markdown content to extract title, description, and links.
+//! Parses markdown content to extract title, description, links and tasks.
 //! Uses pulldown-cmark under the hood.
 
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// The syntax a link was written in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    /// Inline or reference markdown link: `[text](path.md)`, `[text][ref]`
+    #[default]
+    Markdown,
+    /// Wiki link: `[[target]]`, `[[target|alias]]`, `[[target#heading]]`
+    Wiki,
+}
 
 /// A link found in the markdown document.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ParsedLink {
-    /// The link target (URL or path).
+    /// The link target: a path for markdown links, a stem or title for wiki links.
+    /// Includes any `#heading` or `#^block` suffix.
     pub target: String,
+    /// The syntax the link was written in.
+    #[serde(default)]
+    pub kind: LinkKind,
+    /// Display text of a piped wiki link (`[[target|alias]]`).
+    #[serde(default)]
+    pub alias: Option<String>,
+    /// Link text as displayed (a wiki link without an alias shows its target).
+    #[serde(default)]
+    pub text: String,
+    /// Plain text of the paragraph, heading or list item containing the link.
+    #[serde(default)]
+    pub context: String,
     /// 1-based line number where the link appears.
     pub line: usize,
     /// 1-based column number where the link appears.
     pub column: usize,
 }
 
+impl ParsedLink {
+    /// The target without its `#heading` suffix (empty for same-document links).
+    pub fn path(&self) -> &str {
+        self.target
+            .split_once('#')
+            .map_or(self.target.as_str(), |(path, _)| path)
+    }
+
+    /// The `#heading` or `#^block` suffix, without the `#`.
+    pub fn anchor(&self) -> Option<&str> {
+        self.target.split_once('#').map(|(_, anchor)| anchor)
+    }
+}
+
+/// Convert heading text into a GitHub-style anchor slug.
+///
+/// Lowercases, drops punctuation other than `-` and `_`, and turns spaces
+/// into hyphens. Unlike `utils::slugify`, repeated hyphens are kept.
+pub fn anchor_slug(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A heading in the document outline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Heading {
+    /// Heading level (1-6).
+    pub level: u8,
+    /// Plain heading text.
+    pub text: String,
+    /// GitHub-style anchor slug, unique within the document.
+    pub anchor: String,
+    /// 1-based line number where the heading appears.
+    pub line: usize,
+}
+
+/// A task list item: `- [ ] text` or `- [x] text`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Task {
+    /// Plain text of the item as written, including any metadata.
+    pub text: String,
+    pub done: bool,
+    /// Date from a `due:YYYY-MM-DD` word.
+    pub due: Option<String>,
+    /// Names from `@name` words, without the `@`.
+    pub owners: Vec<String>,
+    /// 1-based line number of the item.
+    pub line: usize,
+}
+
+impl Task {
+    fn new(text: &str, done: bool, line: usize) -> Self {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let mut due = None;
+        let mut owners = Vec::new();
+        for word in text.split(' ') {
+            if let Some(date) = word.strip_prefix("due:") {
+                let date = date.trim_end_matches(|c: char| !c.is_ascii_digit());
+                if due.is_none() && NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok() {
+                    due = Some(date.to_string());
+                }
+            } else if let Some(name) = word.strip_prefix('@') {
+                let name = name.trim_end_matches(|c: char| !c.is_alphanumeric());
+                if !name.is_empty() && !owners.iter().any(|o| o == name) {
+                    owners.push(name.to_string());
+                }
+            }
+        }
+        Task {
+            text,
+            done,
+            due,
+            owners,
+            line,
+        }
+    }
+}
+
 /// Parsed content extracted from a markdown document.
 #[derive(Debug, Clone, Default)]
 pub struct ParsedMarkdown {
@@ -23,8 +135,12 @@ pub struct ParsedMarkdown {
     pub title: Option<String>,
     /// Description (first paragraph after the title).
     pub description: Option<String>,
-    /// Local file links found in the document (with line numbers).
+    /// Local file and wiki links found in the document (with line numbers).
     pub links: Vec<ParsedLink>,
+    /// All headings in document order.
+    pub headings: Vec<Heading>,
+    /// Task list items in document order.
+    pub tasks: Vec<Task>,
 }
 
 /// Position in a document (1-based line and column).
@@ -42,87 +158,316 @@ fn offset_to_position(content: &str, offset: usize) -> Position {
     Position { line, column }
 }
 
-/// Parse markdown content and extract title, description, and links.
+/// Parse markdown content and extract title, description, links, headings
+/// and tasks.
 pub fn parse(content: &str) -> ParsedMarkdown {
-    use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+    use pulldown_cmark::{Event, HeadingLevel, LinkType, Options, Parser, Tag, TagEnd};
 
-    let parser = Parser::new_ext(content, Options::empty()).into_offset_iter();
+    let options = Options::ENABLE_WIKILINKS | Options::ENABLE_TASKLISTS;
+    let parser = Parser::new_ext(content, options).into_offset_iter();
 
     let mut result = ParsedMarkdown::default();
 
-    let mut in_h1 = false;
     let mut after_h1 = false;
     let mut in_first_paragraph = false;
-    let mut h1_text = String::new();
     let mut paragraph_text = String::new();
+    // Level, text and start offset of the heading being read
+    let mut heading: Option<(HeadingLevel, String, usize)> = None;
+    // Number of times each anchor has been used, for `-1`, `-2` suffixes
+    let mut anchor_counts: HashMap<String, usize> = HashMap::new();
+    // Text of the current link, and whether it's a piped wiki link
+    let mut link_text: Option<(String, bool)> = None;
+    // Text of the current block, and the first link inside it
+    let mut block_text = String::new();
+    let mut block_first_link = 0;
+    // One entry per open list item: its done state, text and line if it's a task
+    let mut items: Vec<Option<(bool, String, usize)>> = Vec::new();
 
     for (event, range) in parser {
+        // Every link in a block gets the block's text as context
+        let block_boundary = match &event {
+            Event::Start(tag) => !is_inline(TagEnd::from(tag.clone())),
+            Event::End(tag) => !is_inline(*tag),
+            _ => false,
+        };
+        if block_boundary {
+            set_context(&mut result.links[block_first_link..], &block_text);
+            block_text.clear();
+            block_first_link = result.links.len();
+        }
+
         match event {
-            // Track when we enter/exit an H1 heading
-            Event::Start(Tag::Heading {
-                level: HeadingLevel::H1,
-                ..
-            }) => {
-                if result.title.is_none() {
-                    in_h1 = true;
-                }
+            // Track headings for the outline; the first H1 is the title
+            Event::Start(Tag::Heading { level, .. }) => {
+                heading = Some((level, String::new(), range.start));
             }
-            Event::End(TagEnd::Heading(HeadingLevel::H1)) => {
-                if in_h1 {
-                    in_h1 = false;
-                    after_h1 = true;
-                    let trimmed = h1_text.trim().to_string();
-                    if !trimmed.is_empty() {
-                        result.title = Some(trimmed);
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some((level, text, start)) = heading.take() {
+                    let text = text.trim().to_string();
+                    if level == HeadingLevel::H1 && result.title.is_none() {
+                        after_h1 = true;
+                        if !text.is_empty() {
+                            result.title = Some(text.clone());
+                        }
                     }
+
+                    let base = anchor_slug(&text);
+                    let count = anchor_counts.entry(base.clone()).or_insert(0);
+                    let anchor = if *count == 0 {
+                        base
+                    } else {
+                        format!("{}-{}", base, count)
+                    };
+                    *count += 1;
+
+                    result.headings.push(Heading {
+                        level: level as u8,
+                        text,
+                        anchor,
+                        line: offset_to_position(content, start).line,
+                    });
                 }
             }
 
             // Track first paragraph after H1 for description
-            Event::Start(Tag::Paragraph) => {
-                if after_h1 && result.description.is_none() {
-                    in_first_paragraph = true;
+            Event::Start(Tag::Paragraph) if after_h1 && result.description.is_none() => {
+                in_first_paragraph = true;
+            }
+            Event::End(TagEnd::Paragraph) if in_first_paragraph => {
+                in_first_paragraph = false;
+                let trimmed = paragraph_text.trim().to_string();
+                if !trimmed.is_empty() {
+                    result.description = Some(trimmed);
                 }
             }
-            Event::End(TagEnd::Paragraph) => {
-                if in_first_paragraph {
-                    in_first_paragraph = false;
-                    let trimmed = paragraph_text.trim().to_string();
-                    if !trimmed.is_empty() {
-                        result.description = Some(trimmed);
-                    }
+
+            // Collect task list items; nested items are tasks of their own
+            Event::Start(Tag::Item) => items.push(None),
+            Event::TaskListMarker(done) => {
+                if let Some(item) = items.last_mut() {
+                    let line = offset_to_position(content, range.start).line;
+                    *item = Some((done, String::new(), line));
+                }
+            }
+            Event::End(TagEnd::Item) => {
+                if let Some(Some((done, text, line))) = items.pop() {
+                    result.tasks.push(Task::new(&text, done, line));
                 }
             }
 
             // Collect text
             Event::Text(text) | Event::Code(text) => {
-                if in_h1 {
-                    h1_text.push_str(&text);
+                block_text.push_str(&text);
+                if let Some(Some((_, task, _))) = items.last_mut() {
+                    task.push_str(&text);
+                }
+                if let Some((link, _)) = link_text.as_mut() {
+                    link.push_str(&text);
+                }
+                if let Some((_, heading_text, _)) = heading.as_mut() {
+                    heading_text.push_str(&text);
                 } else if in_first_paragraph {
                     paragraph_text.push_str(&text);
                 }
             }
 
             // Collect local links with line and column numbers
-            Event::Start(Tag::Link { dest_url, .. }) => {
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                ..
+            }) => {
                 let url = dest_url.as_ref();
                 if !url.starts_with("http://") && !url.starts_with("https://") && !url.is_empty() {
+                    let (kind, piped) = match link_type {
+                        LinkType::WikiLink { has_pothole } => (LinkKind::Wiki, has_pothole),
+                        _ => (LinkKind::Markdown, false),
+                    };
+                    link_text = Some((String::new(), piped));
                     let pos = offset_to_position(content, range.start);
                     result.links.push(ParsedLink {
                         target: url.to_string(),
+                        kind,
+                        alias: None,
+                        text: String::new(),
+                        context: String::new(),
                         line: pos.line,
                         column: pos.column,
                     });
                 }
             }
+            Event::End(TagEnd::Link) => {
+                if let (Some((text, piped)), Some(link)) =
+                    (link_text.take(), result.links.last_mut())
+                {
+                    link.text = text.trim().to_string();
+                    if piped {
+                        link.alias = Some(link.text.clone());
+                    }
+                }
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                block_text.push(' ');
+                if let Some(Some((_, task, _))) = items.last_mut() {
+                    task.push(' ');
+                }
+                if let Some((link, _)) = link_text.as_mut() {
+                    link.push(' ');
+                }
+            }
 
             _ => {}
         }
     }
+    set_context(&mut result.links[block_first_link..], &block_text);
+    // Nested tasks end before their parents
+    result.tasks.sort_by_key(|task| task.line);
 
     result
 }
 
+/// Where a link's destination is written, for rewriting it in place.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkSpan {
+    pub kind: LinkKind,
+    /// The destination as written, including any `#anchor` (and `<...>`
+    /// around markdown destinations).
+    pub target: String,
+    /// Byte range of `target` in the content.
+    pub range: Range<usize>,
+}
+
+/// Find the destination of every local inline link or image, reference
+/// definition and wiki link, in document order. Links in code and external
+/// URLs are skipped.
+pub fn link_spans(content: &str) -> Vec<LinkSpan> {
+    use pulldown_cmark::{Event, LinkType, Options, Parser, Tag, TagEnd};
+
+    let parser = Parser::new_ext(content, Options::ENABLE_WIKILINKS);
+    let mut spans: Vec<LinkSpan> = parser
+        .reference_definitions()
+        .iter()
+        .filter_map(|(_, def)| {
+            // `[label]: destination "title"`
+            let after = def.span.start + content[def.span.clone()].find("]:")? + 2;
+            destination_span(content, after, def.span.end)
+        })
+        .collect();
+
+    // End of each open inline link or image, and the end of its text so far
+    let mut inline: Vec<(usize, usize)> = Vec::new();
+    for (event, range) in parser.into_offset_iter() {
+        match event {
+            Event::Start(Tag::Link {
+                link_type: LinkType::WikiLink { .. },
+                ..
+            }) => {
+                // `[[target|alias]]` or `[[target]]`
+                let start = range.start + 2;
+                let inner = &content[start..range.end];
+                let end = start + inner.find(['|', ']']).unwrap_or(inner.len());
+                spans.push(LinkSpan {
+                    kind: LinkKind::Wiki,
+                    target: content[start..end].to_string(),
+                    range: start..end,
+                });
+            }
+            Event::Start(
+                Tag::Link {
+                    link_type: LinkType::Inline,
+                    ..
+                }
+                | Tag::Image {
+                    link_type: LinkType::Inline,
+                    ..
+                },
+            ) => {
+                // Text starts after `[` (or `![`)
+                let text_start = range.start + content[range.clone()].find('[').unwrap_or(0) + 1;
+                inline.push((range.end, text_start));
+            }
+            Event::End(TagEnd::Link | TagEnd::Image) if !inline.is_empty() => {
+                let (end, text_end) = inline.pop().unwrap_or_default();
+                // The destination follows the text's closing `](`
+                let after = content[text_end..end].find("](").map(|i| text_end + i + 2);
+                spans.extend(after.and_then(|after| destination_span(content, after, end)));
+                if let Some((_, outer_text_end)) = inline.last_mut() {
+                    *outer_text_end = (*outer_text_end).max(end);
+                }
+            }
+            _ => {
+                if let Some((_, text_end)) = inline.last_mut() {
+                    *text_end = (*text_end).max(range.end);
+                }
+            }
+        }
+    }
+
+    spans.retain(|span| !span.target.contains("://") && !span.target.starts_with("mailto:"));
+    spans.sort_by_key(|span| span.range.start);
+    spans
+}
+
+/// Find a markdown link destination starting at or after `start`.
+fn destination_span(content: &str, start: usize, end: usize) -> Option<LinkSpan> {
+    let rest = &content[start..end];
+    let start = start + (rest.len() - rest.trim_start().len());
+    let rest = &content[start..end];
+
+    let len = if rest.starts_with('<') {
+        rest.find('>')? + 1
+    } else {
+        // Up to whitespace or an unbalanced `)`
+        let mut depth = 0usize;
+        rest.char_indices()
+            .find(|&(_, c)| match c {
+                '(' => {
+                    depth += 1;
+                    false
+                }
+                ')' if depth == 0 => true,
+                ')' => {
+                    depth -= 1;
+                    false
+                }
+                c => c.is_whitespace(),
+            })
+            .map_or(rest.len(), |(i, _)| i)
+    };
+    if len == 0 {
+        return None;
+    }
+
+    Some(LinkSpan {
+        kind: LinkKind::Markdown,
+        target: content[start..start + len].to_string(),
+        range: start..start + len,
+    })
+}
+
+/// Whether a tag is inline formatting rather than a block.
+fn is_inline(tag: pulldown_cmark::TagEnd) -> bool {
+    use pulldown_cmark::TagEnd;
+    matches!(
+        tag,
+        TagEnd::Emphasis
+            | TagEnd::Strong
+            | TagEnd::Strikethrough
+            | TagEnd::Superscript
+            | TagEnd::Subscript
+            | TagEnd::Link
+            | TagEnd::Image
+    )
+}
+
+/// Give links the text of the block they're in, with whitespace collapsed.
+fn set_context(links: &mut [ParsedLink], text: &str) {
+    let context = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    for link in links {
+        link.context = context.clone();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -170,6 +515,160 @@ mod tests {
         assert_eq!(result.links[1].column, 1);
     }
 
+    #[test]
+    fn test_parse_wiki_links() {
+        let result = parse("See [[topic-b]] and [[Topic C|the third]].");
+        assert_eq!(result.links.len(), 2);
+        assert_eq!(result.links[0].target, "topic-b");
+        assert_eq!(result.links[0].kind, LinkKind::Wiki);
+        assert_eq!(result.links[0].alias, None);
+        assert_eq!(result.links[0].column, 5);
+        assert_eq!(result.links[1].target, "Topic C");
+        assert_eq!(result.links[1].alias, Some("the third".to_string()));
+    }
+
+    #[test]
+    fn test_parse_wiki_link_suffixes() {
+        let result = parse("[[notes#Setup Guide]] [[notes#^block-1|ref]] [[#Local]]");
+        assert_eq!(result.links.len(), 3);
+        assert_eq!(result.links[0].target, "notes#Setup Guide");
+        assert_eq!(result.links[0].path(), "notes");
+        assert_eq!(result.links[1].target, "notes#^block-1");
+        assert_eq!(result.links[1].path(), "notes");
+        assert_eq!(result.links[1].alias, Some("ref".to_string()));
+        assert_eq!(result.links[2].target, "#Local");
+        assert_eq!(result.links[2].path(), "");
+    }
+
+    #[test]
+    fn test_link_text_and_context() {
+        let result = parse(
+            "# Title\n\nSee [the *plan*](plan.md)\nfor details.\n\n- Item with [[topic-b]]\n  - Nested [[Topic C|third]]\n\n## About [x](x.md)\n",
+        );
+        let links: Vec<(&str, &str)> = result
+            .links
+            .iter()
+            .map(|l| (l.text.as_str(), l.context.as_str()))
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                ("the plan", "See the plan for details."),
+                ("topic-b", "Item with topic-b"),
+                ("third", "Nested third"),
+                ("x", "About x"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_link_spans() {
+        let content = "See [a [nested] link](./a.md#intro \"Title\") and [b](<my b.md>) [![i](i.png)](c.md).\n\n[[wiki#Heading|alias]] [ref][r] [web](https://x.io)\n\n`[c](c.md)`\n\n[r]:  ../ref.md\n";
+        let spans = link_spans(content);
+        let spans: Vec<(LinkKind, &str)> = spans
+            .iter()
+            .map(|span| {
+                assert_eq!(&content[span.range.clone()], span.target);
+                (span.kind, span.target.as_str())
+            })
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (LinkKind::Markdown, "./a.md#intro"),
+                (LinkKind::Markdown, "<my b.md>"),
+                (LinkKind::Markdown, "i.png"),
+                (LinkKind::Markdown, "c.md"),
+                (LinkKind::Wiki, "wiki#Heading"),
+                (LinkKind::Markdown, "../ref.md"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_markdown_link_kind() {
+        let result = parse("[a](a.md#intro)");
+        assert_eq!(result.links[0].kind, LinkKind::Markdown);
+        assert_eq!(result.links[0].path(), "a.md");
+        assert_eq!(result.links[0].anchor(), Some("intro"));
+    }
+
+    #[test]
+    fn test_wiki_link_in_code_is_ignored() {
+        let result = parse("Use `[[not-a-link]]` syntax.\n\n```\n[[also-not]]\n```");
+        assert!(result.links.is_empty());
+    }
+
+    #[test]
+    fn test_parse_headings() {
+        let result = parse("# Title\n\n## Getting Started!\n\ntext\n\n### `code` & More\n");
+        let outline: Vec<(u8, &str, &str, usize)> = result
+            .headings
+            .iter()
+            .map(|h| (h.level, h.text.as_str(), h.anchor.as_str(), h.line))
+            .collect();
+        assert_eq!(
+            outline,
+            vec![
+                (1, "Title", "title", 1),
+                (2, "Getting Started!", "getting-started", 3),
+                (3, "code & More", "code--more", 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_heading_anchors() {
+        let result = parse("## Notes\n\n## Notes\n\n## Notes");
+        let anchors: Vec<&str> = result.headings.iter().map(|h| h.anchor.as_str()).collect();
+        assert_eq!(anchors, vec!["notes", "notes-1", "notes-2"]);
+    }
+
+    #[test]
+    fn test_title_is_first_h1_only() {
+        let result = parse("## Intro\n\n# Real Title\n\n# Second H1");
+        assert_eq!(result.title, Some("Real Title".to_string()));
+        assert_eq!(result.headings.len(), 3);
+    }
+
+    #[test]
+    fn test_anchor_slug() {
+        assert_eq!(anchor_slug("Hello World"), "hello-world");
+        assert_eq!(anchor_slug("API v2.0 (beta)"), "api-v20-beta");
+        assert_eq!(anchor_slug("snake_case-name"), "snake_case-name");
+        assert_eq!(anchor_slug("Café Notes"), "café-notes");
+    }
+
+    #[test]
+    fn test_tasks() {
+        let content = "# Plan\n\n\
+                       - [ ] Write the spec due:2026-11-01 @sam\n\
+                       - [x] Book a room for @alex, @sam.\n  \
+                       - [ ] Nested `task` with [a link](other.md)\n\
+                       - Not a task\n\n\
+                       ```\n- [ ] In code\n```\n";
+        let result = parse(content);
+
+        let tasks: Vec<(usize, bool, &str)> = result
+            .tasks
+            .iter()
+            .map(|t| (t.line, t.done, t.text.as_str()))
+            .collect();
+        assert_eq!(
+            tasks,
+            vec![
+                (3, false, "Write the spec due:2026-11-01 @sam"),
+                (4, true, "Book a room for @alex, @sam."),
+                (5, false, "Nested task with a link"),
+            ]
+        );
+        assert_eq!(result.tasks[0].due.as_deref(), Some("2026-11-01"));
+        assert_eq!(result.tasks[0].owners, vec!["sam"]);
+        assert_eq!(result.tasks[1].due, None);
+        assert_eq!(result.tasks[1].owners, vec!["alex", "sam"]);
+        assert_eq!(result.links.len(), 1);
+    }
+
     #[test]
     fn test_empty_content() {
         let result = parse("");
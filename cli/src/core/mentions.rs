@@ -0,0 +1,247 @@
+//! Unlinked mentions.
+//!
+//! Finds plain-text occurrences of a topic's title or aliases in other notes,
+//! so they can be reported or turned into links.
+
+use std::ops::Range;
+
+use super::frontmatter;
+
+/// Characters of context kept on each side of a mention in its snippet.
+const SNIPPET_CONTEXT: usize = 30;
+
+/// A mention of a topic that isn't a link.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mention {
+    /// The mention as written in the note.
+    pub text: String,
+    /// Byte range of the mention in the file.
+    pub range: Range<usize>,
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column number.
+    pub column: usize,
+    /// The surrounding text on the same line.
+    pub snippet: String,
+}
+
+/// Find case-insensitive, whole-word occurrences of any of `terms` in a file.
+///
+/// Only prose in the body is searched: frontmatter, code, HTML, links and
+/// images are skipped. Where terms overlap, the longest match wins.
+pub fn find(content: &str, terms: &[String]) -> Vec<Mention> {
+    use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+
+    let mut terms: Vec<&str> = terms
+        .iter()
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+        .collect();
+    terms.sort_by_key(|t| std::cmp::Reverse(t.chars().count()));
+    if terms.is_empty() {
+        return Vec::new();
+    }
+
+    // The body is always a suffix of the file, so offsets map back directly
+    let body_start = content.len() - frontmatter::parse(content).body.len();
+    let body = &content[body_start..];
+
+    // Merge adjacent text events, which pulldown-cmark splits at
+    // punctuation, into runs of prose
+    let mut runs: Vec<Range<usize>> = Vec::new();
+    let mut skip_depth = 0usize;
+    for (event, range) in Parser::new_ext(body, Options::ENABLE_WIKILINKS).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Link { .. } | Tag::Image { .. } | Tag::CodeBlock(_)) => {
+                skip_depth += 1
+            }
+            Event::End(TagEnd::Link | TagEnd::Image | TagEnd::CodeBlock) => {
+                skip_depth = skip_depth.saturating_sub(1)
+            }
+            Event::Text(_) if skip_depth == 0 => match runs.last_mut() {
+                Some(last) if last.end == range.start => last.end = range.end,
+                _ => runs.push(range),
+            },
+            _ => {}
+        }
+    }
+
+    let mut mentions = Vec::new();
+    for run in runs {
+        let text = &body[run.clone()];
+        let mut offset = 0;
+        while offset < text.len() {
+            let found = word_start(text, offset)
+                .then(|| terms.iter().find_map(|term| match_at(text, offset, term)))
+                .flatten();
+            match found {
+                Some(end) => {
+                    let start = body_start + run.start + offset;
+                    let end = body_start + run.start + end;
+                    mentions.push(mention(content, start..end));
+                    offset = end - body_start - run.start;
+                }
+                None => {
+                    offset += text[offset..].chars().next().map_or(1, char::len_utf8);
+                }
+            }
+        }
+    }
+    mentions
+}
+
+/// Replace each mention with a markdown link to `href`, keeping its text.
+///
+/// Mentions must come from [`find`] on the same content.
+pub fn link(content: &str, mentions: &[&Mention], href: &str) -> String {
+    let mut sorted: Vec<&&Mention> = mentions.iter().collect();
+    sorted.sort_by_key(|m| std::cmp::Reverse(m.range.start));
+
+    let mut result = content.to_string();
+    for mention in sorted {
+        result.replace_range(
+            mention.range.clone(),
+            &format!("[{}]({})", mention.text, href),
+        );
+    }
+    result
+}
+
+/// Whether a word can start at `offset` (it isn't preceded by a word character).
+fn word_start(text: &str, offset: usize) -> bool {
+    !text[..offset].chars().next_back().is_some_and(is_word_char)
+}
+
+/// If `term` occurs case-insensitively at `offset` as a whole word, return
+/// the byte offset where it ends.
+fn match_at(text: &str, offset: usize, term: &str) -> Option<usize> {
+    let mut chars = text[offset..].char_indices();
+    for expected in term.chars() {
+        let (_, actual) = chars.next()?;
+        if !actual.to_lowercase().eq(expected.to_lowercase()) {
+            return None;
+        }
+    }
+    let end = chars.next().map_or(text.len(), |(i, _)| offset + i);
+    if text[end..].chars().next().is_some_and(is_word_char) {
+        return None;
+    }
+    Some(end)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Build a mention for a byte range, with its position and snippet.
+fn mention(content: &str, range: Range<usize>) -> Mention {
+    let line_start = content[..range.start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = content[range.end..]
+        .find('\n')
+        .map_or(content.len(), |i| range.end + i);
+
+    let before: Vec<char> = content[line_start..range.start].chars().collect();
+    let after: Vec<char> = content[range.end..line_end].trim_end().chars().collect();
+    let mut snippet = String::new();
+    if before.len() > SNIPPET_CONTEXT {
+        snippet.push('…');
+        snippet.extend(&before[before.len() - SNIPPET_CONTEXT..]);
+    } else {
+        snippet.extend(before.iter().skip_while(|c| c.is_whitespace()));
+    }
+    snippet.push_str(&content[range.clone()]);
+    snippet.extend(after.iter().take(SNIPPET_CONTEXT));
+    if after.len() > SNIPPET_CONTEXT {
+        snippet.push('…');
+    }
+
+    Mention {
+        text: content[range.clone()].to_string(),
+        line: content[..range.start].matches('\n').count() + 1,
+        column: before.len() + 1,
+        range,
+        snippet,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(terms: &[&str]) -> Vec<String> {
+        terms.iter().map(|t| t.to_string()).collect()
+    }
+
+    fn found(content: &str, names: &[&str]) -> Vec<(String, usize, usize)> {
+        find(content, &terms(names))
+            .into_iter()
+            .map(|m| (m.text, m.line, m.column))
+            .collect()
+    }
+
+    #[test]
+    fn finds_whole_words_case_insensitively() {
+        assert_eq!(
+            found(
+                "# Notes\n\nWe use rust daily. Rust, not Rustacean or trust.",
+                &["Rust"]
+            ),
+            vec![("rust".to_string(), 3, 8), ("Rust".to_string(), 3, 20)]
+        );
+    }
+
+    #[test]
+    fn skips_frontmatter_links_and_code() {
+        let content = "---\ntitle: Project Plan\n---\n\n# Notes\n\nSee [Project Plan](plan.md), [[Project Plan]], `Project Plan`.\n\n```\nProject Plan\n```\n\nThe project plan is due.\n";
+        assert_eq!(
+            found(content, &["Project Plan"]),
+            vec![("project plan".to_string(), 13, 5)]
+        );
+    }
+
+    #[test]
+    fn prefers_longest_term() {
+        assert_eq!(
+            found(
+                "Ask about Project Plan B.",
+                &["Project Plan", "Project Plan B"]
+            ),
+            vec![("Project Plan B".to_string(), 1, 11)]
+        );
+    }
+
+    #[test]
+    fn matches_across_punctuation_and_unicode() {
+        assert_eq!(
+            found("Learning C++ and Café [Tips].", &["C++", "café"]),
+            vec![("C++".to_string(), 1, 10), ("Café".to_string(), 1, 18)]
+        );
+    }
+
+    #[test]
+    fn snippets_are_trimmed_to_context() {
+        let content = format!("{} Topic A {}", "x".repeat(40), "y".repeat(40));
+        let mention = &find(&content, &terms(&["Topic A"]))[0];
+        assert_eq!(
+            mention.snippet,
+            format!("…{} Topic A {}…", "x".repeat(29), "y".repeat(29))
+        );
+
+        let mention = &find("  Short Topic A line.  ", &terms(&["Topic A"]))[0];
+        assert_eq!(mention.snippet, "Short Topic A line.");
+    }
+
+    #[test]
+    fn links_selected_mentions() {
+        let content = "# Notes\n\nTopic A and topic a again.\n";
+        let mentions = find(content, &terms(&["Topic A"]));
+        assert_eq!(
+            link(content, &[&mentions[1]], "topic-a.md"),
+            "# Notes\n\nTopic A and [topic a](topic-a.md) again.\n"
+        );
+        assert_eq!(
+            link(content, &mentions.iter().collect::<Vec<_>>(), "a.md"),
+            "# Notes\n\n[Topic A](a.md) and [topic a](a.md) again.\n"
+        );
+    }
+}
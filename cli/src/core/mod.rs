@@ -1,6 +1,17 @@
+pub mod cache;
+pub mod changes;
 pub mod frontmatter;
+pub mod graph;
+pub mod history;
 pub mod index;
+pub mod links;
 pub mod markdown;
+pub mod mentions;
 pub mod query;
+pub mod rank;
+pub mod relink;
+pub mod schema;
+pub mod settings;
 pub mod template;
 pub mod topic;
+pub mod trash;
@@ -1,42 +1,445 @@
-use std::collections::HashMap;
+use anyhow::{bail, Result};
+use chrono::NaiveDate;
+use std::cmp::Ordering;
 
-/// A parsed search query with structured filters and free-text terms.
+/// A node in a parsed query expression tree.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// Field filter (e.g., status:active, priority>=2, due:2026-01-01..2026-03-31)
+    Filter { key: String, condition: Condition },
+    /// Free-text search term
+    Term(String),
+    /// Negated sub-expression (`-expr` or `NOT expr`)
+    Not(Box<Expr>),
+    /// All sub-expressions must match
+    And(Vec<Expr>),
+    /// At least one sub-expression must match
+    Or(Vec<Expr>),
+}
+
+/// A parsed search query.
 #[derive(Debug, Default)]
 pub struct Query {
-    /// Key-value filters (e.g., status:active, priority:high)
-    pub filters: HashMap<String, Vec<String>>,
-    /// Free-text search terms
-    pub terms: Vec<String>,
+    /// Root of the expression tree (`None` for an empty query)
+    pub expr: Option<Expr>,
 }
 
 impl Query {
-    /// Parse a query string into structured filters and free-text terms.
+    /// Parse a query string into an expression tree.
     ///
     /// Syntax:
-    /// - `key:value` → adds to filters
-    /// - `bare word` → adds to terms
-    /// - Multiple values for same key: `tags:work tags:personal`
-    pub fn parse(input: &str) -> Self {
-        let mut query = Query::default();
-
-        for token in input.split_whitespace() {
-            if let Some((key, value)) = token.split_once(':') {
-                let key = key.to_lowercase();
-                let value = value.to_lowercase();
-                if !value.is_empty() {
-                    query.filters.entry(key).or_default().push(value);
-                }
-            } else {
-                query.terms.push(token.to_lowercase());
-            }
+    /// - `key:value` → field filter
+    /// - `key<value`, `key<=value`, `key>value`, `key>=value` → numeric/date comparison
+    /// - `key:low..high` → inclusive numeric/date range (either bound optional)
+    /// - `bare word` or `"quoted phrase"` → free-text term
+    /// - `a b` or `a AND b` → both must match
+    /// - `a OR b` → either must match
+    /// - `-a` or `NOT a` → must not match
+    /// - `( ... )` → grouping
+    ///
+    /// `AND` binds tighter than `OR`, so `a b OR c` means `(a b) OR c`.
+    pub fn parse(input: &str) -> Result<Self> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+
+        if parser.tokens.is_empty() {
+            return Ok(Query::default());
+        }
+
+        let expr = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            bail!("Unexpected {} in query", token.describe());
         }
 
-        query
+        Ok(Query { expr: Some(expr) })
     }
 
     /// Check if query is empty (no filters or terms).
     pub fn is_empty(&self) -> bool {
-        self.filters.is_empty() && self.terms.is_empty()
+        self.expr.is_none()
+    }
+
+    /// Whether the query is only free-text words, with no filters, negation
+    /// or `OR`.
+    pub fn is_plain(&self) -> bool {
+        match &self.expr {
+            None | Some(Expr::Term(_)) => true,
+            Some(Expr::And(parts)) => parts.iter().all(|e| matches!(e, Expr::Term(_))),
+            Some(_) => false,
+        }
+    }
+
+    /// Free-text terms that count towards a match (terms under a negation are excluded).
+    pub fn terms(&self) -> Vec<&str> {
+        fn collect<'a>(expr: &'a Expr, out: &mut Vec<&'a str>) {
+            match expr {
+                Expr::Term(term) => out.push(term),
+                Expr::And(parts) | Expr::Or(parts) => {
+                    parts.iter().for_each(|e| collect(e, out));
+                }
+                Expr::Filter { .. } | Expr::Not(_) => {}
+            }
+        }
+
+        let mut terms = Vec::new();
+        if let Some(expr) = &self.expr {
+            collect(expr, &mut terms);
+        }
+        terms
+    }
+}
+
+/// A lexical token in a query string.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    /// A word or quoted phrase. `literal` is set when the token started with a quote,
+    /// so it is never treated as a keyword or filter.
+    Word {
+        text: String,
+        literal: bool,
+    },
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::LParen => "'('".to_string(),
+            Token::RParen => "')'".to_string(),
+            Token::And => "'AND'".to_string(),
+            Token::Or => "'OR'".to_string(),
+            Token::Not => "'NOT'".to_string(),
+            Token::Word { text, .. } => format!("'{}'", text),
+        }
+    }
+}
+
+/// Split a query string into tokens.
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            '-' => {
+                // A leading dash negates the following word or group
+                chars.next();
+                match chars.peek() {
+                    Some(&next) if !next.is_whitespace() && next != ')' => tokens.push(Token::Not),
+                    _ => tokens.push(Token::Word {
+                        text: "-".to_string(),
+                        literal: false,
+                    }),
+                }
+            }
+            _ => {
+                let literal = c == '"';
+                let mut text = String::new();
+                let mut in_quotes = false;
+
+                while let Some(&c) = chars.peek() {
+                    if c == '"' {
+                        in_quotes = !in_quotes;
+                    } else if !in_quotes && (c.is_whitespace() || c == '(' || c == ')') {
+                        break;
+                    } else {
+                        text.push(c);
+                    }
+                    chars.next();
+                }
+
+                if in_quotes {
+                    bail!("Unterminated quote in query");
+                }
+
+                let token = match text.as_str() {
+                    "AND" if !literal => Token::And,
+                    "OR" if !literal => Token::Or,
+                    "NOT" if !literal => Token::Not,
+                    _ => Token::Word { text, literal },
+                };
+                tokens.push(token);
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive-descent parser over a token stream.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// `or_expr = and_expr ("OR" and_expr)*`
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut branches = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            branches.push(self.parse_and()?);
+        }
+        Ok(flatten(branches, Expr::Or))
+    }
+
+    /// `and_expr = unary (["AND"] unary)*`
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut parts = vec![self.parse_unary()?];
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.next();
+                    parts.push(self.parse_unary()?);
+                }
+                Some(Token::Or) | Some(Token::RParen) | None => break,
+                Some(_) => parts.push(self.parse_unary()?),
+            }
+        }
+        Ok(flatten(parts, Expr::And))
+    }
+
+    /// `unary = ("NOT" | "-") unary | primary`
+    fn parse_unary(&mut self) -> Result<Expr> {
+        if self.peek() == Some(&Token::Not) {
+            self.next();
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    /// `primary = "(" or_expr ")" | filter | term`
+    fn parse_primary(&mut self) -> Result<Expr> {
+        match self.next() {
+            Some(Token::LParen) => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(expr),
+                    _ => bail!("Missing closing ')' in query"),
+                }
+            }
+            Some(Token::Word { text, literal }) => word_to_expr(text, literal),
+            Some(token) => bail!("Unexpected {} in query", token.describe()),
+            None => bail!("Unexpected end of query"),
+        }
+    }
+}
+
+/// Collapse single-element groups so `a` parses as `a`, not `And([a])`.
+fn flatten(mut items: Vec<Expr>, wrap: fn(Vec<Expr>) -> Expr) -> Expr {
+    if items.len() == 1 {
+        items.remove(0)
+    } else {
+        wrap(items)
+    }
+}
+
+/// Turn a word token into a filter or a free-text term.
+fn word_to_expr(text: String, literal: bool) -> Result<Expr> {
+    if !literal {
+        if let Some(idx) = text.find([':', '<', '>']) {
+            if idx > 0 {
+                let key = text[..idx].to_lowercase();
+                let rest = &text[idx..];
+                let condition = Condition::parse(rest.strip_prefix(':').unwrap_or(rest))?;
+                return Ok(Expr::Filter { key, condition });
+            }
+        }
+    }
+    Ok(Expr::Term(text.to_lowercase()))
+}
+
+/// Comparison operator for typed filters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompareOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    /// Check whether an ordering between field and filter value satisfies this operator.
+    fn accepts(self, ordering: Ordering) -> bool {
+        match self {
+            CompareOp::Lt => ordering == Ordering::Less,
+            CompareOp::Le => ordering != Ordering::Greater,
+            CompareOp::Gt => ordering == Ordering::Greater,
+            CompareOp::Ge => ordering != Ordering::Less,
+        }
+    }
+}
+
+/// Compare a field value against a bound; values of different kinds never match.
+fn compare(value: Scalar, op: CompareOp, bound: Scalar) -> bool {
+    value
+        .partial_cmp(&bound)
+        .is_some_and(|ordering| op.accepts(ordering))
+}
+
+/// What a filter requires of a field's value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    /// `key:value` — case-insensitive substring match, or exact match for numbers and dates.
+    /// An empty value only requires the field to be present.
+    Matches(String),
+    /// `key<value` etc. — semantic comparison against a number or date.
+    Compare(CompareOp, Scalar),
+    /// `key:low..high` — inclusive range; a missing bound is unbounded.
+    Range(Option<Scalar>, Option<Scalar>),
+}
+
+impl Condition {
+    /// Parse the part of a filter after the key (and after `:` if present).
+    fn parse(input: &str) -> Result<Self> {
+        for value in input.trim_start_matches(['<', '>', '=']).split("..") {
+            Date::check(value)?;
+        }
+
+        let ops = [
+            ("<=", CompareOp::Le),
+            (">=", CompareOp::Ge),
+            ("<", CompareOp::Lt),
+            (">", CompareOp::Gt),
+        ];
+        for (prefix, op) in ops {
+            if let Some(value) = input.strip_prefix(prefix) {
+                return match Scalar::parse(value) {
+                    Some(scalar) => Ok(Condition::Compare(op, scalar)),
+                    None => bail!(
+                        "Expected a number or date after '{}', got '{}'",
+                        prefix,
+                        value
+                    ),
+                };
+            }
+        }
+
+        // Only treat `..` as a range when the bounds are comparable values,
+        // so `path:../notes` still works as a substring filter.
+        if let Some((low, high)) = input.split_once("..") {
+            let low_bound = Scalar::parse(low);
+            let high_bound = Scalar::parse(high);
+            let valid = match (&low_bound, &high_bound) {
+                (Some(l), Some(h)) => l.partial_cmp(h).is_some(),
+                (Some(_), None) => high.is_empty(),
+                (None, Some(_)) => low.is_empty(),
+                (None, None) => false,
+            };
+            if valid {
+                return Ok(Condition::Range(low_bound, high_bound));
+            }
+        }
+
+        Ok(Condition::Matches(input.to_lowercase()))
+    }
+}
+
+/// A calendar date parsed from an ISO 8601 string (`YYYY-MM-DD`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// Parse `YYYY-MM-DD`, ignoring any trailing time component (`T10:00`, ` 10:00`).
+    fn parse(input: &str) -> Option<Self> {
+        let (year, month, day) = Self::fields(input)?;
+        NaiveDate::from_ymd_opt(year, month, day)?;
+        Some(Date { year, month, day })
+    }
+
+    /// Reject input shaped like a date that isn't one, such as `2024-02-31`.
+    fn check(input: &str) -> Result<()> {
+        let input = input.trim();
+        if Self::fields(input).is_some() && Self::parse(input).is_none() {
+            bail!("Invalid date '{}'", input);
+        }
+        Ok(())
+    }
+
+    /// The year, month and day of a `YYYY-MM-DD` string, unvalidated.
+    fn fields(input: &str) -> Option<(i32, u32, u32)> {
+        let date = input.get(..10)?;
+        let rest = &input[10..];
+        if !(rest.is_empty() || rest.starts_with('T') || rest.starts_with(' ')) {
+            return None;
+        }
+
+        let mut parts = date.split('-');
+        let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+        if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+            return None;
+        }
+        Some((year.parse().ok()?, month.parse().ok()?, day.parse().ok()?))
+    }
+}
+
+/// A typed value that can be compared semantically.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scalar {
+    Number(f64),
+    Date(Date),
+}
+
+impl Scalar {
+    /// Detect a number or ISO date in a string.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if let Some(date) = Date::parse(input) {
+            return Some(Scalar::Date(date));
+        }
+        // Require a leading digit, sign or dot so words like "inf" and "nan" stay strings
+        let numeric_start = input
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.'));
+        if numeric_start {
+            if let Ok(n) = input.parse::<f64>() {
+                if n.is_finite() {
+                    return Some(Scalar::Number(n));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl PartialOrd for Scalar {
+    /// Numbers compare with numbers and dates with dates; mixed kinds are unordered.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Scalar::Number(a), Scalar::Number(b)) => a.partial_cmp(b),
+            (Scalar::Date(a), Scalar::Date(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
     }
 }
 
@@ -50,13 +453,46 @@ pub enum FieldValue {
 }
 
 impl FieldValue {
+    /// Iterate over the individual values.
+    fn values(&self) -> &[String] {
+        match self {
+            FieldValue::Single(v) => std::slice::from_ref(v),
+            FieldValue::Multiple(vs) => vs,
+        }
+    }
+
     /// Check if this field value matches the given filter value.
+    ///
+    /// Numbers and dates must be equal (`priority:1` does not match `10`);
+    /// anything else is a case-insensitive substring match.
     pub fn matches(&self, filter: &str) -> bool {
-        match self {
-            FieldValue::Single(v) => v.to_lowercase().contains(filter),
-            FieldValue::Multiple(vs) => vs.iter().any(|v| v.to_lowercase().contains(filter)),
+        let typed = Scalar::parse(filter);
+        self.values()
+            .iter()
+            .any(|v| match (typed, Scalar::parse(v)) {
+                (Some(a), Some(b)) if a.partial_cmp(&b).is_some() => a == b,
+                _ => v.to_lowercase().contains(filter),
+            })
+    }
+
+    /// Check if this field value satisfies a filter condition.
+    ///
+    /// For list fields, any element satisfying the condition is enough.
+    pub fn satisfies(&self, condition: &Condition) -> bool {
+        match condition {
+            Condition::Matches(value) => value.is_empty() || self.matches(value),
+            Condition::Compare(op, bound) => self.scalars().any(|v| compare(v, *op, *bound)),
+            Condition::Range(low, high) => self.scalars().any(|v| {
+                low.is_none_or(|l| compare(v, CompareOp::Ge, l))
+                    && high.is_none_or(|h| compare(v, CompareOp::Le, h))
+            }),
         }
     }
+
+    /// Values that parse as numbers or dates.
+    fn scalars(&self) -> impl Iterator<Item = Scalar> + '_ {
+        self.values().iter().filter_map(|v| Scalar::parse(v))
+    }
 }
 
 /// A trait for items that can be matched against a query.
@@ -70,71 +506,386 @@ pub trait Queryable {
 
 /// Check if an item matches the query.
 pub fn matches<T: Queryable>(item: &T, query: &Query) -> bool {
-    // Check all filters
-    for (key, values) in &query.filters {
-        match item.get_field(key) {
-            Some(field) => {
-                // All filter values for this key must match
-                if !values.iter().all(|v| field.matches(v)) {
-                    return false;
-                }
-            }
-            None => return false,
+    match &query.expr {
+        Some(expr) => {
+            let mut haystack = None;
+            eval(item, expr, &mut haystack)
         }
+        None => true,
     }
+}
 
-    // Check free-text terms
-    if !query.terms.is_empty() {
-        let haystack = item.searchable_text().to_lowercase();
-        if !query.terms.iter().all(|term| haystack.contains(term)) {
-            return false;
-        }
+/// Evaluate an expression against an item.
+///
+/// The lowercased searchable text is computed lazily and shared across terms.
+fn eval<T: Queryable>(item: &T, expr: &Expr, haystack: &mut Option<String>) -> bool {
+    match expr {
+        Expr::Filter { key, condition } => item
+            .get_field(key)
+            .is_some_and(|field| field.satisfies(condition)),
+        Expr::Term(term) => haystack
+            .get_or_insert_with(|| item.searchable_text().to_lowercase())
+            .contains(term.as_str()),
+        Expr::Not(inner) => !eval(item, inner, haystack),
+        Expr::And(parts) => parts.iter().all(|e| eval(item, e, haystack)),
+        Expr::Or(branches) => branches.iter().any(|e| eval(item, e, haystack)),
     }
-
-    true
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn filter(key: &str, value: &str) -> Expr {
+        Expr::Filter {
+            key: key.to_string(),
+            condition: Condition::Matches(value.to_string()),
+        }
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> Scalar {
+        Scalar::Date(Date { year, month, day })
+    }
+
+    fn term(t: &str) -> Expr {
+        Expr::Term(t.to_string())
+    }
+
+    fn parse(input: &str) -> Expr {
+        Query::parse(input).unwrap().expr.unwrap()
+    }
+
+    /// Minimal queryable item for evaluation tests.
+    struct Item {
+        status: Option<&'static str>,
+        tags: Vec<&'static str>,
+        text: &'static str,
+    }
+
+    /// Queryable item with a single numeric or date field.
+    struct Valued(&'static str, &'static str);
+
+    impl Queryable for Valued {
+        fn get_field(&self, key: &str) -> Option<FieldValue> {
+            (key == self.0).then(|| FieldValue::Single(self.1.to_string()))
+        }
+
+        fn searchable_text(&self) -> String {
+            String::new()
+        }
+    }
+
+    impl Queryable for Item {
+        fn get_field(&self, key: &str) -> Option<FieldValue> {
+            match key {
+                "status" => self.status.map(|s| FieldValue::Single(s.to_string())),
+                "tags" => Some(FieldValue::Multiple(
+                    self.tags.iter().map(|t| t.to_string()).collect(),
+                )),
+                _ => None,
+            }
+        }
+
+        fn searchable_text(&self) -> String {
+            self.text.to_string()
+        }
+    }
+
+    fn item(status: Option<&'static str>, tags: Vec<&'static str>, text: &'static str) -> Item {
+        Item { status, tags, text }
+    }
+
+    fn matches_str<T: Queryable>(item: &T, query: &str) -> bool {
+        matches(item, &Query::parse(query).unwrap())
+    }
+
     #[test]
     fn parse_empty() {
-        let q = Query::parse("");
+        let q = Query::parse("").unwrap();
         assert!(q.is_empty());
+        assert!(Query::parse("   ").unwrap().is_empty());
     }
 
     #[test]
     fn parse_filters_only() {
-        let q = Query::parse("status:active priority:high");
-        assert_eq!(q.filters.get("status"), Some(&vec!["active".to_string()]));
-        assert_eq!(q.filters.get("priority"), Some(&vec!["high".to_string()]));
-        assert!(q.terms.is_empty());
+        assert_eq!(
+            parse("status:active priority:high"),
+            Expr::And(vec![filter("status", "active"), filter("priority", "high")])
+        );
     }
 
     #[test]
     fn parse_terms_only() {
-        let q = Query::parse("meeting notes");
-        assert!(q.filters.is_empty());
-        assert_eq!(q.terms, vec!["meeting", "notes"]);
+        assert_eq!(
+            parse("meeting notes"),
+            Expr::And(vec![term("meeting"), term("notes")])
+        );
     }
 
     #[test]
     fn parse_mixed() {
-        let q = Query::parse("status:active quarterly review");
-        assert_eq!(q.filters.get("status"), Some(&vec!["active".to_string()]));
-        assert_eq!(q.terms, vec!["quarterly", "review"]);
+        assert_eq!(
+            parse("status:active quarterly review"),
+            Expr::And(vec![
+                filter("status", "active"),
+                term("quarterly"),
+                term("review")
+            ])
+        );
     }
 
     #[test]
-    fn parse_multiple_same_key() {
-        let q = Query::parse("tags:work tags:important");
+    fn parse_single_token_is_not_wrapped() {
+        assert_eq!(parse("Status:Active"), filter("status", "active"));
+    }
+
+    #[test]
+    fn parse_or_has_lower_precedence_than_and() {
+        assert_eq!(
+            parse("a b OR c"),
+            Expr::Or(vec![Expr::And(vec![term("a"), term("b")]), term("c")])
+        );
+    }
+
+    #[test]
+    fn parse_explicit_and() {
+        assert_eq!(parse("a AND b"), Expr::And(vec![term("a"), term("b")]));
+    }
+
+    #[test]
+    fn parse_negation() {
+        assert_eq!(
+            parse("-status:done"),
+            Expr::Not(Box::new(filter("status", "done")))
+        );
+        assert_eq!(parse("NOT draft"), Expr::Not(Box::new(term("draft"))));
+    }
+
+    #[test]
+    fn parse_groups() {
+        assert_eq!(
+            parse("(status:active OR status:blocked) -tag:archive"),
+            Expr::And(vec![
+                Expr::Or(vec![
+                    filter("status", "active"),
+                    filter("status", "blocked")
+                ]),
+                Expr::Not(Box::new(filter("tag", "archive"))),
+            ])
+        );
+        assert_eq!(
+            parse("-(a OR b)"),
+            Expr::Not(Box::new(Expr::Or(vec![term("a"), term("b")])))
+        );
+    }
+
+    #[test]
+    fn parse_quoted_phrases() {
+        assert_eq!(parse("\"quarterly review\""), term("quarterly review"));
+        assert_eq!(parse("\"OR\""), term("or"));
+        assert_eq!(
+            parse("status:\"in progress\""),
+            filter("status", "in progress")
+        );
+    }
+
+    #[test]
+    fn parse_lowercase_keywords_are_terms() {
         assert_eq!(
-            q.filters.get("tags"),
-            Some(&vec!["work".to_string(), "important".to_string()])
+            parse("this or that"),
+            Expr::And(vec![term("this"), term("or"), term("that")])
         );
     }
 
+    #[test]
+    fn parse_hyphenated_word_is_not_negated() {
+        assert_eq!(parse("well-known"), term("well-known"));
+    }
+
+    #[test]
+    fn terms_skip_filters_and_negations() {
+        let q = Query::parse("rust (async OR tokio) -draft status:active").unwrap();
+        assert_eq!(q.terms(), vec!["rust", "async", "tokio"]);
+        assert!(Query::parse("").unwrap().terms().is_empty());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(Query::parse("(a OR b").is_err());
+        assert!(Query::parse("a)").is_err());
+        assert!(Query::parse("a OR").is_err());
+        assert!(Query::parse("\"unterminated").is_err());
+        assert!(Query::parse("()").is_err());
+    }
+
+    #[test]
+    fn parse_comparisons() {
+        assert_eq!(
+            parse("due<2026-03-01"),
+            Expr::Filter {
+                key: "due".to_string(),
+                condition: Condition::Compare(CompareOp::Lt, date(2026, 3, 1)),
+            }
+        );
+        assert_eq!(
+            parse("estimate>=3"),
+            Expr::Filter {
+                key: "estimate".to_string(),
+                condition: Condition::Compare(CompareOp::Ge, Scalar::Number(3.0)),
+            }
+        );
+        assert_eq!(
+            parse("due:>2026-01-01"),
+            Expr::Filter {
+                key: "due".to_string(),
+                condition: Condition::Compare(CompareOp::Gt, date(2026, 1, 1)),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_ranges() {
+        assert_eq!(
+            parse("estimate:1..5"),
+            Expr::Filter {
+                key: "estimate".to_string(),
+                condition: Condition::Range(Some(Scalar::Number(1.0)), Some(Scalar::Number(5.0))),
+            }
+        );
+        assert_eq!(
+            parse("due:2026-01-01.."),
+            Expr::Filter {
+                key: "due".to_string(),
+                condition: Condition::Range(Some(date(2026, 1, 1)), None),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_non_range_dots_are_substring() {
+        assert_eq!(parse("path:../notes"), filter("path", "../notes"));
+        assert_eq!(parse("v:1..2026-01-01"), filter("v", "1..2026-01-01"));
+    }
+
+    #[test]
+    fn plain_queries() {
+        assert!(Query::parse("").unwrap().is_plain());
+        assert!(Query::parse("weekly sync").unwrap().is_plain());
+        assert!(!Query::parse("status:active").unwrap().is_plain());
+        assert!(!Query::parse("a OR b").unwrap().is_plain());
+        assert!(!Query::parse("sync -draft").unwrap().is_plain());
+    }
+
+    #[test]
+    fn parse_comparison_requires_typed_value() {
+        assert!(Query::parse("status<active").is_err());
+        assert!(Query::parse("due>=").is_err());
+    }
+
+    #[test]
+    fn parse_rejects_impossible_dates() {
+        for q in [
+            "due:2024-02-31",
+            "due<2023-02-29",
+            "due:2024-01-01..2024-04-31",
+        ] {
+            let err = Query::parse(q).unwrap_err().to_string();
+            assert!(err.contains("Invalid date"), "{}: {}", q, err);
+        }
+        assert!(Query::parse("due:2024-02-29").is_ok());
+    }
+
+    #[test]
+    fn scalar_detection() {
+        assert_eq!(Scalar::parse("42"), Some(Scalar::Number(42.0)));
+        assert_eq!(Scalar::parse("-1.5"), Some(Scalar::Number(-1.5)));
+        assert_eq!(Scalar::parse("2026-03-01"), Some(date(2026, 3, 1)));
+        assert_eq!(Scalar::parse("2026-03-01T09:30:00"), Some(date(2026, 3, 1)));
+        assert_eq!(Scalar::parse("2026-13-01"), None);
+        assert_eq!(Scalar::parse("2026-02-30"), None);
+        assert_eq!(Scalar::parse("2026-03"), None);
+        assert_eq!(Scalar::parse("inf"), None);
+        assert_eq!(Scalar::parse("active"), None);
+    }
+
+    #[test]
+    fn eval_numeric_comparisons() {
+        let item = Valued("estimate", "5");
+        assert!(matches_str(&item, "estimate>=3"));
+        assert!(matches_str(&item, "estimate<=5"));
+        assert!(!matches_str(&item, "estimate>5"));
+        assert!(!matches_str(&item, "estimate<3"));
+        // Numeric, not lexicographic: "5" > "10" as strings
+        assert!(matches_str(&item, "estimate<10"));
+    }
+
+    #[test]
+    fn eval_date_comparisons() {
+        let item = Valued("due", "2026-02-15");
+        assert!(matches_str(&item, "due<2026-03-01"));
+        assert!(!matches_str(&item, "due>2026-03-01"));
+        assert!(matches_str(&item, "due:2026-02-01..2026-02-28"));
+        assert!(!matches_str(&item, "due:2026-03-01.."));
+        assert!(matches_str(&item, "due:..2026-02-15"));
+    }
+
+    #[test]
+    fn eval_mixed_kinds_never_compare() {
+        let item = Valued("due", "2026-02-15");
+        assert!(!matches_str(&item, "due>3"));
+        let text = Valued("due", "someday");
+        assert!(!matches_str(&text, "due<2026-03-01"));
+    }
+
+    #[test]
+    fn eval_number_equality_is_exact() {
+        let item = Valued("priority", "10");
+        assert!(!matches_str(&item, "priority:1"));
+        assert!(matches_str(&item, "priority:10"));
+        assert!(matches_str(&item, "priority:10.0"));
+    }
+
+    #[test]
+    fn eval_partial_date_still_substring() {
+        let item = Valued("due", "2026-03-14");
+        assert!(matches_str(&item, "due:2026-03"));
+        assert!(matches_str(&item, "due:2026-03-14"));
+        assert!(!matches_str(&item, "due:2026-03-01"));
+    }
+
+    #[test]
+    fn eval_or() {
+        let active = item(Some("active"), vec![], "");
+        let blocked = item(Some("blocked"), vec![], "");
+        let done = item(Some("done"), vec![], "");
+        let q = "status:active OR status:blocked";
+        assert!(matches_str(&active, q));
+        assert!(matches_str(&blocked, q));
+        assert!(!matches_str(&done, q));
+    }
+
+    #[test]
+    fn eval_not_matches_missing_field() {
+        let done = item(Some("done"), vec![], "");
+        let none = item(None, vec![], "");
+        assert!(!matches_str(&done, "-status:done"));
+        assert!(matches_str(&none, "-status:done"));
+    }
+
+    #[test]
+    fn eval_grouped_terms_and_filters() {
+        let a = item(Some("active"), vec!["work"], "Quarterly Review");
+        let q = "(tags:work OR tags:personal) quarterly -status:done";
+        assert!(matches_str(&a, q));
+        assert!(!matches_str(&a, "(tags:home OR tags:personal) quarterly"));
+    }
+
+    #[test]
+    fn eval_empty_query_matches_everything() {
+        let a = item(None, vec![], "");
+        assert!(matches_str(&a, ""));
+    }
+
     #[test]
     fn field_value_single_match() {
         let v = FieldValue::Single("active".to_string());
@@ -150,4 +901,11 @@ mod tests {
         assert!(v.matches("important"));
         assert!(!v.matches("personal"));
     }
+
+    #[test]
+    fn field_value_multiple_satisfies_any() {
+        let v = FieldValue::Multiple(vec!["1".to_string(), "8".to_string()]);
+        assert!(v.satisfies(&Condition::Compare(CompareOp::Gt, Scalar::Number(5.0))));
+        assert!(!v.satisfies(&Condition::Compare(CompareOp::Gt, Scalar::Number(9.0))));
+    }
 }
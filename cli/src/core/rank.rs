@@ -0,0 +1,144 @@
+//! Relevance ranking for free-text search.
+//!
+//! Scores topics with BM25 over the title and body, counting title
+//! occurrences more heavily so that topics *about* a term rank above
+//! topics that merely mention it.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::topic::Topic;
+
+/// Term frequency saturation.
+const K1: f64 = 1.2;
+/// Document length normalisation.
+const B: f64 = 0.75;
+/// Weight of a title occurrence relative to a body occurrence.
+const TITLE_BOOST: f64 = 3.0;
+
+/// Split text into lowercase alphanumeric words.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Weighted occurrences of each query word in one topic.
+struct DocStats {
+    /// Weighted term frequency per query word.
+    freqs: Vec<f64>,
+    /// Weighted document length in words.
+    length: f64,
+}
+
+impl DocStats {
+    fn new(topic: &Topic, words: &[String]) -> Self {
+        let title = tokenize(&topic.title);
+        let body = tokenize(&topic.body);
+
+        // Prefix matching mirrors the substring semantics of query matching,
+        // so "meet" counts occurrences of "meeting".
+        let count = |tokens: &[String], word: &str| {
+            tokens.iter().filter(|t| t.starts_with(word)).count() as f64
+        };
+
+        DocStats {
+            freqs: words
+                .iter()
+                .map(|w| TITLE_BOOST * count(&title, w) + count(&body, w))
+                .collect(),
+            length: TITLE_BOOST * title.len() as f64 + body.len() as f64,
+        }
+    }
+}
+
+/// Score every topic in the corpus against the given search terms.
+///
+/// Document frequencies and average length are taken from the whole corpus,
+/// so scores are comparable across any subset of it.
+pub fn scores(corpus: &[Topic], terms: &[&str]) -> HashMap<PathBuf, f64> {
+    let words: Vec<String> = terms.iter().flat_map(|t| tokenize(t)).collect();
+    if words.is_empty() || corpus.is_empty() {
+        return HashMap::new();
+    }
+
+    let docs: Vec<DocStats> = corpus.iter().map(|t| DocStats::new(t, &words)).collect();
+
+    let n = docs.len() as f64;
+    let avg_length = (docs.iter().map(|d| d.length).sum::<f64>() / n).max(1.0);
+    let idf: Vec<f64> = (0..words.len())
+        .map(|i| {
+            let df = docs.iter().filter(|d| d.freqs[i] > 0.0).count() as f64;
+            (1.0 + (n - df + 0.5) / (df + 0.5)).ln()
+        })
+        .collect();
+
+    corpus
+        .iter()
+        .zip(&docs)
+        .map(|(topic, doc)| {
+            let norm = K1 * (1.0 - B + B * doc.length / avg_length);
+            let score = doc
+                .freqs
+                .iter()
+                .zip(&idf)
+                .map(|(&tf, &idf)| idf * tf * (K1 + 1.0) / (tf + norm))
+                .sum();
+            (topic.path.clone(), score)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn topic(name: &str, content: &str) -> Topic {
+        Topic::from_content(Path::new(name), content)
+    }
+
+    #[test]
+    fn tokenize_splits_on_punctuation() {
+        assert_eq!(
+            tokenize("Q1 Planning: well-known [link](x.md)"),
+            vec!["q1", "planning", "well", "known", "link", "x", "md"]
+        );
+    }
+
+    #[test]
+    fn title_match_outranks_body_match() {
+        let corpus = vec![
+            topic("a.md", "# Notes\n\nWe discussed rust today."),
+            topic("b.md", "# Rust\n\nA systems language."),
+        ];
+        let scores = scores(&corpus, &["rust"]);
+        assert!(scores[Path::new("b.md")] > scores[Path::new("a.md")]);
+    }
+
+    #[test]
+    fn more_occurrences_rank_higher() {
+        let corpus = vec![
+            topic("a.md", "# A\n\nTokio once."),
+            topic("b.md", "# B\n\nTokio, tokio and more tokio."),
+            topic("c.md", "# C\n\nNothing relevant."),
+        ];
+        let scores = scores(&corpus, &["tokio"]);
+        assert!(scores[Path::new("b.md")] > scores[Path::new("a.md")]);
+        assert_eq!(scores[Path::new("c.md")], 0.0);
+    }
+
+    #[test]
+    fn prefix_matches_count() {
+        let corpus = vec![topic("a.md", "# Meeting notes")];
+        let scores = scores(&corpus, &["meet"]);
+        assert!(scores[Path::new("a.md")] > 0.0);
+    }
+
+    #[test]
+    fn no_terms_yields_no_scores() {
+        let corpus = vec![topic("a.md", "# A")];
+        assert!(scores(&corpus, &[]).is_empty());
+    }
+}
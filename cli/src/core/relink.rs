@@ -0,0 +1,233 @@
+//! Link rewriting for moved and renamed topics.
+//!
+//! Rewrites link destinations in place, using the byte ranges found by
+//! [`markdown::link_spans`], so text around the links, code blocks and the
+//! style each link was written in are left alone.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::frontmatter;
+use super::links::{self, normalize, LinkResolver};
+use super::markdown::{self, LinkKind};
+use super::topic::Topic;
+
+/// A topic's new location, and its new title if that changes too.
+struct Destination {
+    path: PathBuf,
+    title: Option<String>,
+}
+
+/// A set of topic moves, used to update links to and from the moved topics.
+pub struct Relocation<'a> {
+    resolver: LinkResolver<'a>,
+    moves: HashMap<PathBuf, Destination>,
+}
+
+impl<'a> Relocation<'a> {
+    /// Start with no moves. `topics` is the index, used to resolve wiki links.
+    pub fn new(topics: &'a [Topic]) -> Self {
+        Relocation {
+            resolver: LinkResolver::new(topics),
+            moves: HashMap::new(),
+        }
+    }
+
+    /// Record that the topic at `from` moves to `to`, optionally with a new title.
+    pub fn add(&mut self, from: &Path, to: &Path, title: Option<String>) {
+        self.moves.insert(
+            normalize(from),
+            Destination {
+                path: to.to_path_buf(),
+                title,
+            },
+        );
+    }
+
+    /// Where a file ends up: its destination if it's moving, otherwise where it is.
+    fn location(&self, path: &Path) -> PathBuf {
+        let path = normalize(path);
+        match self.moves.get(&path) {
+            Some(destination) => normalize(&destination.path),
+            None => path,
+        }
+    }
+
+    /// Rewrite the links in the content of the file at `path` (its current location).
+    ///
+    /// Links to moved topics are pointed at their new location. If the file
+    /// itself moves, its relative links are recomputed from the new location.
+    /// Unresolved links are left as written.
+    pub fn rewrite(&self, content: &str, path: &Path) -> String {
+        // Spans are found in the body, which is always a suffix of the file
+        let body_start = content.len() - frontmatter::parse(content).body.len();
+        let new_source = self.location(path);
+
+        let mut result = content.to_string();
+        for span in markdown::link_spans(&content[body_start..]).iter().rev() {
+            let replacement = match span.kind {
+                LinkKind::Markdown => self.markdown_target(&span.target, path, &new_source),
+                LinkKind::Wiki => self.wiki_target(&span.target),
+            };
+            if let Some(replacement) = replacement {
+                let range = span.range.start + body_start..span.range.end + body_start;
+                result.replace_range(range, &replacement);
+            }
+        }
+        result
+    }
+
+    /// New destination for a markdown link, if it needs to change.
+    fn markdown_target(&self, target: &str, source: &Path, new_source: &Path) -> Option<String> {
+        let unwrapped = target
+            .strip_prefix('<')
+            .and_then(|t| t.strip_suffix('>'))
+            .unwrap_or(target);
+        let (path, anchor) = match unwrapped.split_once('#') {
+            Some((path, anchor)) => (path, Some(anchor)),
+            None => (unwrapped, None),
+        };
+        if path.is_empty() {
+            return None;
+        }
+
+        // Resolve as `LinkResolver` does, with `.md` implied if missing
+        let resolved = normalize(&source.parent()?.join(path));
+        let with_ext = resolved.with_extension("md");
+        let old_target = if self.moves.contains_key(&resolved) || resolved.exists() {
+            resolved
+        } else if resolved.extension().is_none()
+            && (self.moves.contains_key(&with_ext) || with_ext.exists())
+        {
+            with_ext
+        } else {
+            return None;
+        };
+
+        let new_target = self.location(&old_target);
+        if new_target == old_target && new_source == normalize(source) {
+            return None;
+        }
+
+        let mut href = links::relative_href(new_source, &new_target);
+        let wrapped = href.starts_with('<');
+        if wrapped {
+            href = href[1..href.len() - 1].to_string();
+        }
+        // Keep the style the link was written in
+        if !path.ends_with(".md") && old_target.extension().is_some_and(|e| e == "md") {
+            href = href.strip_suffix(".md").unwrap_or(&href).to_string();
+        }
+        if path.starts_with("./") && !href.starts_with("../") {
+            href = format!("./{}", href);
+        }
+        if let Some(anchor) = anchor {
+            href = format!("{}#{}", href, anchor);
+        }
+        if wrapped || target.starts_with('<') {
+            href = format!("<{}>", href);
+        }
+
+        (href != target).then_some(href)
+    }
+
+    /// New target for a wiki link to a moved topic, written the same way:
+    /// by partial path, file stem or title.
+    fn wiki_target(&self, target: &str) -> Option<String> {
+        let (path, anchor) = match target.split_once('#') {
+            Some((path, anchor)) => (path, Some(anchor)),
+            None => (target, None),
+        };
+        let topic = self.resolver.resolve_wiki(path)?;
+        let destination = self.moves.get(&normalize(&topic.path))?;
+
+        let trimmed = path.trim().strip_suffix(".md").unwrap_or(path.trim());
+        let new_path = destination.path.with_extension("");
+        let by_stem = topic
+            .path
+            .file_stem()
+            .is_some_and(|s| s.to_string_lossy().eq_ignore_ascii_case(trimmed));
+
+        let mut new_target = if trimmed.contains('/') {
+            // Keep as many trailing components as the link had
+            let count = trimmed.split('/').filter(|c| !c.is_empty()).count();
+            let components: Vec<String> = new_path
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect();
+            components[components.len().saturating_sub(count)..].join("/")
+        } else if by_stem {
+            new_path.file_name()?.to_string_lossy().to_string()
+        } else {
+            match &destination.title {
+                Some(title) => title.clone(),
+                None => return None,
+            }
+        };
+        if let Some(anchor) = anchor {
+            new_target = format!("{}#{}", new_target, anchor);
+        }
+
+        (new_target != target).then_some(new_target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, Vec<Topic>) {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("projects")).unwrap();
+        fs::write(root.join("old.md"), "# Old Title\n").unwrap();
+        fs::write(root.join("other.md"), "# Other\n").unwrap();
+        fs::write(root.join("image.png"), "").unwrap();
+        fs::write(root.join("projects/plan.md"), "# Plan\n").unwrap();
+        let topics = ["old.md", "other.md", "projects/plan.md"]
+            .iter()
+            .map(|p| Topic::from_path(&root.join(p)).unwrap())
+            .collect();
+        (temp, topics)
+    }
+
+    #[test]
+    fn rewrites_links_to_renamed_topic() {
+        let (temp, topics) = setup();
+        let root = temp.path();
+        let mut relocation = Relocation::new(&topics);
+        relocation.add(
+            &root.join("old.md"),
+            &root.join("new-title.md"),
+            Some("New Title".to_string()),
+        );
+
+        let content = "---\nrelated: old.md\n---\n\n# Plan\n\n[a](../old.md) [b](./../old.md#intro) [c](../old)\n[[old]] [[Old Title|alias]] [[old#Setup]] [[other]]\n\n```\n[x](../old.md)\n```\n\n[ref]: ../old.md \"Old\"\n";
+        assert_eq!(
+            relocation.rewrite(content, &root.join("projects/plan.md")),
+            "---\nrelated: old.md\n---\n\n# Plan\n\n[a](../new-title.md) [b](../new-title.md#intro) [c](../new-title)\n[[new-title]] [[New Title|alias]] [[new-title#Setup]] [[other]]\n\n```\n[x](../old.md)\n```\n\n[ref]: ../new-title.md \"Old\"\n"
+        );
+    }
+
+    #[test]
+    fn rewrites_links_from_moved_topic() {
+        let (temp, topics) = setup();
+        let root = temp.path();
+        let mut relocation = Relocation::new(&topics);
+        relocation.add(&root.join("old.md"), &root.join("projects/old.md"), None);
+
+        let content = "# Old Title\n\n[other](other.md) ![img](./image.png) [plan](projects/plan.md) [[projects/old]] [missing](gone.md)\n";
+        assert_eq!(
+            relocation.rewrite(content, &root.join("old.md")),
+            "# Old Title\n\n[other](../other.md) ![img](../image.png) [plan](plan.md) [[projects/old]] [missing](gone.md)\n"
+        );
+
+        let content = "[[Old Title]] [[old]] [link](old.md) [[projects/plan]]";
+        assert_eq!(
+            relocation.rewrite(content, &root.join("other.md")),
+            "[[Old Title]] [[old]] [link](projects/old.md) [[projects/plan]]"
+        );
+    }
+}
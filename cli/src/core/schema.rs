@@ -0,0 +1,373 @@
+//! User-defined frontmatter schema.
+//!
+//! Declared in `.hypha/schema.toml` in the notes root. Each `[fields.<name>]`
+//! table gives a field's type, whether it is required and, for enums, strings
+//! and lists, which values are allowed. Fields not in the schema are not
+//! checked.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_yaml::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use super::frontmatter::FieldPosition;
+use super::links::LinkResolver;
+use super::query::Scalar;
+use super::topic::{Topic, TopicWarning};
+use crate::constants::{DATA_DIR, SCHEMA_FILE};
+
+/// Frontmatter schema for a notes root.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Schema {
+    pub fields: BTreeMap<String, FieldSchema>,
+}
+
+/// Declaration of a single frontmatter field.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldSchema {
+    #[serde(rename = "type")]
+    pub kind: FieldType,
+    /// Report topics that don't set this field.
+    #[serde(default)]
+    pub required: bool,
+    /// Allowed values (enum and string fields, or list items).
+    #[serde(default)]
+    pub values: Vec<String>,
+}
+
+/// Type of a frontmatter field.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    String,
+    Enum,
+    /// ISO 8601 date (`YYYY-MM-DD`).
+    Date,
+    Number,
+    /// YAML sequence of strings or numbers.
+    List,
+    /// Reference to another topic: a wiki target, `[[wiki link]]` or relative `.md` path.
+    Link,
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FieldType::String => "string",
+            FieldType::Enum => "enum",
+            FieldType::Date => "date",
+            FieldType::Number => "number",
+            FieldType::List => "list",
+            FieldType::Link => "link",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Schema {
+    /// Load the schema for a root. Without a schema file nothing is checked.
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(DATA_DIR).join(SCHEMA_FILE);
+        if !path.exists() {
+            return Ok(Schema::default());
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read schema file: {}", path.display()))?;
+        let schema: Schema = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse schema file: {}", path.display()))?;
+        schema
+            .check()
+            .with_context(|| format!("Invalid schema file: {}", path.display()))?;
+        Ok(schema)
+    }
+
+    /// Reject declarations that can never be satisfied or whose `values` would be ignored.
+    fn check(&self) -> Result<()> {
+        for (name, field) in &self.fields {
+            match field.kind {
+                FieldType::Enum if field.values.is_empty() => {
+                    bail!("Enum field '{}' must list its allowed values", name)
+                }
+                FieldType::Date | FieldType::Number | FieldType::Link
+                    if !field.values.is_empty() =>
+                {
+                    bail!(
+                        "Field '{}' of type {} can't restrict its values",
+                        name,
+                        field.kind
+                    )
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Check a topic's frontmatter against the schema.
+    ///
+    /// `resolver` covers the whole index, and is used to resolve `link` fields.
+    pub fn validate(&self, topic: &Topic, resolver: &LinkResolver) -> Vec<TopicWarning> {
+        let mut warnings = Vec::new();
+
+        for (name, field) in &self.fields {
+            // Point at the field, or the start of the file if it's missing
+            let position = topic
+                .field_positions
+                .get(name)
+                .copied()
+                .unwrap_or(FieldPosition { line: 1, column: 1 });
+
+            match topic.metadata.get(name) {
+                None | Some(Value::Null) => {
+                    if field.required {
+                        warnings.push(TopicWarning::MissingField {
+                            field: name.clone(),
+                            line: position.line,
+                            column: position.column,
+                        });
+                    }
+                }
+                Some(value) => {
+                    warnings.extend(field.check(name, value, position, topic, resolver));
+                }
+            }
+        }
+
+        warnings
+    }
+}
+
+impl FieldSchema {
+    /// Check a present field value, returning at most one warning.
+    fn check(
+        &self,
+        name: &str,
+        value: &Value,
+        position: FieldPosition,
+        topic: &Topic,
+        resolver: &LinkResolver,
+    ) -> Option<TopicWarning> {
+        let invalid_type = || TopicWarning::InvalidFieldType {
+            field: name.to_string(),
+            expected: self.kind.to_string(),
+            line: position.line,
+            column: position.column,
+        };
+
+        let values: Vec<String> = match (self.kind, value) {
+            (FieldType::String, Value::String(s)) => vec![s.clone()],
+            (FieldType::Enum, _) => match scalar_string(value) {
+                Some(value) => vec![value],
+                None => return Some(invalid_type()),
+            },
+            (FieldType::Date, Value::String(s))
+                if matches!(Scalar::parse(s), Some(Scalar::Date(_))) =>
+            {
+                return None
+            }
+            (FieldType::Number, Value::Number(_)) => return None,
+            (FieldType::List, Value::Sequence(items)) => {
+                match items.iter().map(scalar_string).collect::<Option<_>>() {
+                    Some(items) => items,
+                    None => return Some(invalid_type()),
+                }
+            }
+            (FieldType::Link, Value::String(s)) => {
+                if resolves(topic, resolver, s) {
+                    return None;
+                }
+                return Some(TopicWarning::BrokenLink {
+                    target: s.clone(),
+                    line: position.line,
+                    column: position.column,
+                });
+            }
+            _ => return Some(invalid_type()),
+        };
+
+        let disallowed = values
+            .into_iter()
+            .find(|v| !self.values.is_empty() && !self.values.contains(v))?;
+        Some(TopicWarning::InvalidFieldValue {
+            field: name.to_string(),
+            value: disallowed,
+            allowed: self.values.clone(),
+            line: position.line,
+            column: position.column,
+        })
+    }
+}
+
+/// Render a string, number or boolean as it appears in the frontmatter.
+fn scalar_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Check whether a `link` field value points at an existing topic.
+fn resolves(topic: &Topic, resolver: &LinkResolver, value: &str) -> bool {
+    let target = value
+        .trim()
+        .trim_start_matches("[[")
+        .trim_end_matches("]]")
+        .split('|')
+        .next()
+        .unwrap_or_default();
+
+    let is_file = target.ends_with(".md")
+        && topic
+            .path
+            .parent()
+            .is_some_and(|dir| dir.join(target).is_file());
+    is_file || resolver.resolve_wiki(target).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn schema(toml: &str) -> Schema {
+        let schema: Schema = toml::from_str(toml).unwrap();
+        schema.check().unwrap();
+        schema
+    }
+
+    fn validate(schema: &Schema, frontmatter: &str) -> Vec<TopicWarning> {
+        let content = format!("---\n{}---\n\n# Topic\n", frontmatter);
+        let topics = vec![
+            Topic::from_content(Path::new("/notes/topic.md"), &content),
+            Topic::from_content(Path::new("/notes/other.md"), "# Other"),
+        ];
+        schema.validate(&topics[0], &LinkResolver::new(&topics))
+    }
+
+    #[test]
+    fn missing_file_has_no_fields() {
+        let temp = TempDir::new().unwrap();
+        assert!(Schema::load(temp.path()).unwrap().fields.is_empty());
+    }
+
+    #[test]
+    fn reports_missing_required_fields() {
+        let schema = schema("[fields.status]\ntype = \"string\"\nrequired = true\n\n[fields.due]\ntype = \"date\"\n");
+        assert_eq!(
+            validate(&schema, "due: 2026-03-01\n"),
+            vec![TopicWarning::MissingField {
+                field: "status".to_string(),
+                line: 1,
+                column: 1,
+            }]
+        );
+        assert_eq!(
+            validate(&schema, "status:\n"),
+            vec![TopicWarning::MissingField {
+                field: "status".to_string(),
+                line: 2,
+                column: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_wrong_types_at_value_position() {
+        let schema = schema(
+            "[fields.due]\ntype = \"date\"\n\n[fields.estimate]\ntype = \"number\"\n\n[fields.tags]\ntype = \"list\"\n",
+        );
+        let warnings = validate(&schema, "due: next week\nestimate: \"3\"\ntags: work\n");
+        let found: Vec<(&str, &str, usize, usize)> = warnings
+            .iter()
+            .map(|w| match w {
+                TopicWarning::InvalidFieldType {
+                    field,
+                    expected,
+                    line,
+                    column,
+                } => (field.as_str(), expected.as_str(), *line, *column),
+                other => panic!("unexpected warning {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("due", "date", 2, 6),
+                ("estimate", "number", 3, 11),
+                ("tags", "list", 4, 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn accepts_valid_values() {
+        let schema = schema(
+            "[fields.status]\ntype = \"enum\"\nvalues = [\"active\", \"done\"]\n\n[fields.due]\ntype = \"date\"\n\n[fields.tags]\ntype = \"list\"\n\n[fields.parent]\ntype = \"link\"\n",
+        );
+        let warnings = validate(
+            &schema,
+            "status: active\ndue: 2026-03-01\ntags: [a, b]\nparent: \"[[other]]\"\n",
+        );
+        assert!(warnings.is_empty(), "{:?}", warnings);
+    }
+
+    #[test]
+    fn reports_disallowed_values() {
+        let schema = schema(
+            "[fields.status]\ntype = \"enum\"\nvalues = [\"active\", \"done\"]\n\n[fields.tags]\ntype = \"list\"\nvalues = [\"work\", \"home\"]\n",
+        );
+        let warnings = validate(&schema, "status: wip\ntags: [work, play]\n");
+        assert_eq!(
+            warnings,
+            vec![
+                TopicWarning::InvalidFieldValue {
+                    field: "status".to_string(),
+                    value: "wip".to_string(),
+                    allowed: vec!["active".to_string(), "done".to_string()],
+                    line: 2,
+                    column: 9,
+                },
+                TopicWarning::InvalidFieldValue {
+                    field: "tags".to_string(),
+                    value: "play".to_string(),
+                    allowed: vec!["work".to_string(), "home".to_string()],
+                    line: 3,
+                    column: 7,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_unresolved_links() {
+        let schema = schema("[fields.parent]\ntype = \"link\"\n");
+        assert_eq!(
+            validate(&schema, "parent: nowhere\n"),
+            vec![TopicWarning::BrokenLink {
+                target: "nowhere".to_string(),
+                line: 2,
+                column: 9,
+            }]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_declarations() {
+        let enum_without_values: Schema = toml::from_str("[fields.s]\ntype = \"enum\"\n").unwrap();
+        assert!(enum_without_values.check().is_err());
+
+        let number_with_values: Schema =
+            toml::from_str("[fields.n]\ntype = \"number\"\nvalues = [\"1\"]\n").unwrap();
+        assert!(number_with_values.check().is_err());
+
+        assert!(toml::from_str::<Schema>("[fields.s]\ntype = \"text\"\n").is_err());
+    }
+}
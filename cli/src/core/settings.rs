@@ -0,0 +1,204 @@
+//! Per-root settings.
+//!
+//! Loaded from `.hypha/config.toml` in the notes root so they travel with the
+//! notes rather than the user's home directory. Every section is optional;
+//! a missing file means defaults.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::constants::{DATA_DIR, SETTINGS_FILE};
+
+/// Settings for a notes root.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub index: IndexSettings,
+    pub lint: LintSettings,
+    pub journal: JournalSettings,
+    /// Options for named templates, keyed by template name.
+    pub templates: BTreeMap<String, TemplateSettings>,
+}
+
+/// Which files are indexed.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IndexSettings {
+    /// Only index files matching at least one of these globs (relative to the root).
+    pub include: Vec<String>,
+    /// Never index files matching these globs (relative to the root).
+    pub exclude: Vec<String>,
+}
+
+/// Opt-in lint rules that look at how topics link together.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintSettings {
+    /// Report topics with no links to or from other topics.
+    pub orphans: bool,
+    /// Report topics that are linked to but link nowhere.
+    pub dead_ends: bool,
+    /// Report topics linked from at least `hub_threshold` topics.
+    pub hubs: bool,
+    pub hub_threshold: usize,
+}
+
+impl Default for LintSettings {
+    fn default() -> Self {
+        LintSettings {
+            orphans: false,
+            dead_ends: false,
+            hubs: false,
+            hub_threshold: 10,
+        }
+    }
+}
+
+/// Where `hypha today` and `hypha journal` keep dated notes.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JournalSettings {
+    /// Folder, relative to the root.
+    pub folder: String,
+    pub daily: PeriodSettings,
+    pub weekly: PeriodSettings,
+    pub monthly: PeriodSettings,
+}
+
+impl Default for JournalSettings {
+    fn default() -> Self {
+        JournalSettings {
+            folder: "journal".to_string(),
+            daily: PeriodSettings::default(),
+            weekly: PeriodSettings::default(),
+            monthly: PeriodSettings::default(),
+        }
+    }
+}
+
+/// Naming and template for daily, weekly or monthly notes. Unset options use
+/// the period's defaults.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PeriodSettings {
+    /// Date format for the path within the journal folder, without `.md`.
+    pub filename: Option<String>,
+    /// Date format for the note's title.
+    pub title: Option<String>,
+    /// Named template from `.hypha/templates/`.
+    pub template: Option<String>,
+}
+
+/// Options for a named template in `.hypha/templates/`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TemplateSettings {
+    /// Folder, relative to the root, that new topics from this template go in.
+    pub folder: Option<String>,
+    /// Shown by `hypha templates list`.
+    pub description: Option<String>,
+}
+
+impl Settings {
+    /// Load settings for a root, using defaults if the file doesn't exist.
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(DATA_DIR).join(SETTINGS_FILE);
+        if !path.exists() {
+            return Ok(Settings::default());
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read settings file: {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse settings file: {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_settings(root: &Path, content: &str) {
+        let dir = root.join(DATA_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(SETTINGS_FILE), content).unwrap();
+    }
+
+    #[test]
+    fn missing_file_uses_defaults() {
+        let temp = TempDir::new().unwrap();
+        let settings = Settings::load(temp.path()).unwrap();
+        assert!(settings.index.include.is_empty());
+        assert!(settings.index.exclude.is_empty());
+    }
+
+    #[test]
+    fn parses_index_globs() {
+        let temp = TempDir::new().unwrap();
+        write_settings(
+            temp.path(),
+            "[index]\ninclude = [\"notes/**\"]\nexclude = [\"archive/\", \"*.draft.md\"]\n",
+        );
+
+        let settings = Settings::load(temp.path()).unwrap();
+        assert_eq!(settings.index.include, vec!["notes/**"]);
+        assert_eq!(settings.index.exclude, vec!["archive/", "*.draft.md"]);
+    }
+
+    #[test]
+    fn parses_lint_rules() {
+        let temp = TempDir::new().unwrap();
+        write_settings(temp.path(), "[lint]\norphans = true\nhub_threshold = 3\n");
+
+        let settings = Settings::load(temp.path()).unwrap();
+        assert!(settings.lint.orphans);
+        assert!(!settings.lint.dead_ends);
+        assert!(!settings.lint.hubs);
+        assert_eq!(settings.lint.hub_threshold, 3);
+    }
+
+    #[test]
+    fn parses_journal_options() {
+        let temp = TempDir::new().unwrap();
+        assert_eq!(
+            Settings::load(temp.path()).unwrap().journal.folder,
+            "journal"
+        );
+
+        write_settings(
+            temp.path(),
+            "[journal]\nfolder = \"log\"\n\n[journal.weekly]\nfilename = \"%Y/week-%V\"\ntemplate = \"review\"\n",
+        );
+        let journal = Settings::load(temp.path()).unwrap().journal;
+        assert_eq!(journal.folder, "log");
+        assert_eq!(journal.weekly.filename.as_deref(), Some("%Y/week-%V"));
+        assert_eq!(journal.weekly.template.as_deref(), Some("review"));
+        assert!(journal.daily.filename.is_none());
+    }
+
+    #[test]
+    fn parses_template_options() {
+        let temp = TempDir::new().unwrap();
+        write_settings(
+            temp.path(),
+            "[templates.meeting]\nfolder = \"meetings\"\ndescription = \"Meeting notes\"\n\n[templates.adr]\n",
+        );
+
+        let settings = Settings::load(temp.path()).unwrap();
+        let meeting = &settings.templates["meeting"];
+        assert_eq!(meeting.folder.as_deref(), Some("meetings"));
+        assert_eq!(meeting.description.as_deref(), Some("Meeting notes"));
+        assert!(settings.templates["adr"].folder.is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        let temp = TempDir::new().unwrap();
+        write_settings(temp.path(), "[index]\nexclud = [\"archive/\"]\n");
+        assert!(Settings::load(temp.path()).is_err());
+    }
+}
@@ -1,7 +1,21 @@
+use anyhow::{bail, Context, Result};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local};
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
 
-use crate::constants::{DEFAULT_TEMPLATE, TEMPLATE_FILE};
+use crate::constants::{DATA_DIR, DEFAULT_TEMPLATE, TEMPLATES_DIR, TEMPLATE_FILE};
+use crate::utils::{ask, slugify};
+
+/// A named template in `.hypha/templates/`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedTemplate {
+    /// File stem, used with `hypha new --template`.
+    pub name: String,
+    pub path: PathBuf,
+}
 
 /// Load template from .template.md in root, or use default.
 pub fn load(root: &Path) -> String {
@@ -13,19 +27,402 @@ pub fn load(root: &Path) -> String {
     }
 }
 
-/// Render a template with the given title.
-pub fn render(template: &str, title: &str) -> String {
-    template.replace("{title}", title)
+/// The directory holding named templates.
+pub fn dir(root: &Path) -> PathBuf {
+    root.join(DATA_DIR).join(TEMPLATES_DIR)
+}
+
+/// Named templates, sorted by name.
+pub fn list(root: &Path) -> Result<Vec<NamedTemplate>> {
+    let dir = dir(root);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut templates: Vec<NamedTemplate> = fs::read_dir(&dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|e| e == "md"))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            Some(NamedTemplate { name, path })
+        })
+        .collect();
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(templates)
+}
+
+/// Load a named template from `.hypha/templates/<name>.md`.
+pub fn load_named(root: &Path, name: &str) -> Result<String> {
+    let templates = list(root)?;
+    let Some(template) = templates.iter().find(|t| t.name == name) else {
+        if templates.is_empty() {
+            bail!(
+                "Unknown template: {} (no templates in {})",
+                name,
+                dir(root).display()
+            );
+        }
+        let names: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+        bail!(
+            "Unknown template: {} (available: {})",
+            name,
+            names.join(", ")
+        );
+    };
+    fs::read_to_string(&template.path)
+        .with_context(|| format!("Failed to read template: {}", template.path.display()))
+}
+
+/// A placeholder in a template, like `{title}` or `{date:%Y-%m-%d}`.
+#[derive(Debug, Clone, PartialEq)]
+enum Placeholder {
+    Title,
+    Slug,
+    /// Current date, with a chrono format.
+    Date(String),
+    /// Current time, with a chrono format.
+    Time(String),
+    Uuid,
+    /// An environment variable.
+    Env(String),
+    /// A value asked for when the topic is created, or given with `--var`.
+    Prompt(String),
+    /// Everything piped to standard input.
+    Stdin,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Text(String),
+    Placeholder(Placeholder),
+}
+
+/// A parsed template.
+///
+/// `{name}` and `{name:argument}` are placeholders, and an unknown name is
+/// an error. Other braces, like `{}` or a YAML flow map such as `{a: 1}`,
+/// are kept as they are.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+/// Values for a template's placeholders.
+#[derive(Debug, Clone)]
+pub struct Values {
+    pub title: String,
+    pub now: DateTime<Local>,
+    /// Answers for `{prompt:Label}`, keyed by lowercase label.
+    pub answers: HashMap<String, String>,
+    /// Standard input, for `{stdin}`.
+    pub stdin: Option<String>,
+}
+
+impl Values {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            now: Local::now(),
+            answers: HashMap::new(),
+            stdin: None,
+        }
+    }
+
+    /// Gather answers for the template's prompts from `--var` and, for any
+    /// left, the terminal.
+    pub fn gather(tmpl: &Template, title: &str, vars: &[String]) -> Result<Self> {
+        let mut values = Self::new(title);
+        for var in vars {
+            let Some((name, value)) = var.split_once('=') else {
+                bail!("Expected --var name=value, got: {}", var);
+            };
+            values
+                .answers
+                .insert(name.trim().to_lowercase(), value.to_string());
+        }
+
+        let missing: Vec<&str> = tmpl
+            .prompts()
+            .into_iter()
+            .filter(|label| !values.answers.contains_key(&label.to_lowercase()))
+            .collect();
+        if !missing.is_empty() && !io::stdin().is_terminal() {
+            let vars: Vec<String> = missing
+                .iter()
+                .map(|label| format!("--var \"{}=...\"", label.to_lowercase()))
+                .collect();
+            bail!(
+                "The template asks for {}; pass {}",
+                missing.join(", "),
+                vars.join(" ")
+            );
+        }
+        for label in missing {
+            values.answers.insert(label.to_lowercase(), ask(label)?);
+        }
+        Ok(values)
+    }
+}
+
+impl Template {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut rest = text;
+        let mut line = 1;
+
+        while let Some(i) = rest.find('{') {
+            literal.push_str(&rest[..i]);
+            line += rest[..i].matches('\n').count();
+            rest = &rest[i..];
+
+            let inner = rest[1..].split('\n').next().unwrap_or_default();
+            let placeholder = match inner.find('}') {
+                Some(end) if is_placeholder(&inner[..end]) => &inner[..end],
+                _ => {
+                    literal.push_str(&rest[..1]);
+                    rest = &rest[1..];
+                    continue;
+                }
+            };
+
+            if !literal.is_empty() {
+                parts.push(Part::Text(std::mem::take(&mut literal)));
+            }
+            let parsed = parse_placeholder(placeholder)
+                .with_context(|| format!("Invalid template on line {}", line))?;
+            parts.push(Part::Placeholder(parsed));
+            rest = &rest[placeholder.len() + 2..];
+        }
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            parts.push(Part::Text(literal));
+        }
+        Ok(Self { parts })
+    }
+
+    /// Labels of `{prompt:Label}` placeholders, in order, without repeats.
+    pub fn prompts(&self) -> Vec<&str> {
+        let mut labels: Vec<&str> = Vec::new();
+        for part in &self.parts {
+            if let Part::Placeholder(Placeholder::Prompt(label)) = part {
+                if !labels.iter().any(|l| l.eq_ignore_ascii_case(label)) {
+                    labels.push(label);
+                }
+            }
+        }
+        labels
+    }
+
+    /// Whether the template uses `{stdin}`.
+    pub fn reads_stdin(&self) -> bool {
+        self.parts.contains(&Part::Placeholder(Placeholder::Stdin))
+    }
+
+    pub fn render(&self, values: &Values) -> Result<String> {
+        let uuid = uuid::Uuid::new_v4().to_string();
+        let mut output = String::new();
+        for part in &self.parts {
+            let placeholder = match part {
+                Part::Text(text) => {
+                    output.push_str(text);
+                    continue;
+                }
+                Part::Placeholder(placeholder) => placeholder,
+            };
+            match placeholder {
+                Placeholder::Title => output.push_str(&values.title),
+                Placeholder::Slug => output.push_str(&slugify(&values.title)),
+                Placeholder::Date(format) | Placeholder::Time(format) => {
+                    output.push_str(&values.now.format(format).to_string())
+                }
+                Placeholder::Uuid => output.push_str(&uuid),
+                Placeholder::Env(name) => match std::env::var(name) {
+                    Ok(value) => output.push_str(&value),
+                    Err(_) => bail!(
+                        "Environment variable {} isn't set (used by {{env:{}}})",
+                        name,
+                        name
+                    ),
+                },
+                Placeholder::Prompt(label) => match values.answers.get(&label.to_lowercase()) {
+                    Some(value) => output.push_str(value),
+                    None => bail!(
+                        "No value for {{prompt:{}}}; pass --var \"{}=...\"",
+                        label,
+                        label.to_lowercase()
+                    ),
+                },
+                Placeholder::Stdin => match &values.stdin {
+                    Some(input) => output.push_str(input.trim_end()),
+                    None => bail!(
+                        "The template uses {{stdin}}, but nothing was piped to standard input"
+                    ),
+                },
+            }
+        }
+        Ok(output)
+    }
+}
+
+/// Whether `text` is a name, alone or followed by `:` and an argument. A
+/// space after the colon, as in a YAML flow map (`{a: 1}`), means it isn't
+/// a placeholder.
+fn is_placeholder(text: &str) -> bool {
+    let (name, argument) = match text.split_once(':') {
+        Some((name, argument)) => (name, Some(argument)),
+        None => (text, None),
+    };
+    name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !argument.is_some_and(|a| a.starts_with(char::is_whitespace))
+}
+
+/// Parse the text between a placeholder's braces.
+fn parse_placeholder(text: &str) -> Result<Placeholder> {
+    let (name, argument) = match text.split_once(':') {
+        Some((name, argument)) => (name, Some(argument)),
+        None => (text, None),
+    };
+    let placeholder = match (name, argument) {
+        ("title", None) => Placeholder::Title,
+        ("slug", None) => Placeholder::Slug,
+        ("uuid", None) => Placeholder::Uuid,
+        ("stdin", None) => Placeholder::Stdin,
+        ("date", None) => Placeholder::Date("%Y-%m-%d".to_string()),
+        ("time", None) => Placeholder::Time("%H:%M".to_string()),
+        ("date" | "time", Some(format)) => {
+            if format.is_empty() || StrftimeItems::new(format).any(|item| item == Item::Error) {
+                bail!("Invalid date format in {{{}}}", text);
+            }
+            if name == "date" {
+                Placeholder::Date(format.to_string())
+            } else {
+                Placeholder::Time(format.to_string())
+            }
+        }
+        ("env", Some(variable)) if !variable.is_empty() => Placeholder::Env(variable.to_string()),
+        ("prompt", Some(label)) if !label.trim().is_empty() => {
+            Placeholder::Prompt(label.trim().to_string())
+        }
+        ("env" | "prompt", _) => bail!("{{{}}} needs a name, like {{{}:NAME}}", name, name),
+        ("title" | "slug" | "uuid" | "stdin", Some(_)) => {
+            bail!("{{{}}} doesn't take an argument", name)
+        }
+        _ => bail!("Unknown placeholder {{{}}}", text),
+    };
+    Ok(placeholder)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn loads_named_templates() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(dir(root)).unwrap();
+        fs::write(dir(root).join("meeting.md"), "# {title}\n\n## Attendees\n").unwrap();
+        fs::write(dir(root).join("adr.md"), "# ADR: {title}\n").unwrap();
+        fs::write(dir(root).join("notes.txt"), "").unwrap();
+
+        let names: Vec<String> = list(root).unwrap().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["adr", "meeting"]);
+        assert_eq!(
+            load_named(root, "meeting").unwrap(),
+            "# {title}\n\n## Attendees\n"
+        );
+
+        let err = load_named(root, "person").unwrap_err().to_string();
+        assert_eq!(err, "Unknown template: person (available: adr, meeting)");
+    }
+
+    fn values(title: &str) -> Values {
+        let mut values = Values::new(title);
+        values.now = Local.with_ymd_and_hms(2026, 3, 5, 9, 30, 0).unwrap();
+        values
+    }
 
     #[test]
     fn render_replaces_title() {
-        let template = "---\ntitle: {title}\n---\n";
-        let result = render(template, "My Topic");
+        let template = Template::parse("---\ntitle: {title}\n---\n").unwrap();
+        let result = template.render(&values("My Topic")).unwrap();
         assert!(result.contains("title: My Topic"));
     }
+
+    #[test]
+    fn renders_variables() {
+        let template = Template::parse(
+            "{slug} {date} {date:%d/%m} {time} {time:%H%M%S}\n\
+             {prompt:Client} {prompt:client} {env:HYPHA_TEST_OWNER} {stdin}\n",
+        )
+        .unwrap();
+        assert_eq!(template.prompts(), vec!["Client"]);
+        assert!(template.reads_stdin());
+
+        std::env::set_var("HYPHA_TEST_OWNER", "sam");
+        let mut values = values("Q1 Review");
+        values
+            .answers
+            .insert("client".to_string(), "Acme".to_string());
+        values.stdin = Some("pasted\n".to_string());
+        assert_eq!(
+            template.render(&values).unwrap(),
+            "q1-review 2026-03-05 05/03 09:30 093000\nAcme Acme sam pasted\n"
+        );
+    }
+
+    #[test]
+    fn uuids_are_generated_per_render() {
+        let template = Template::parse("{uuid} {uuid}").unwrap();
+        let first = template.render(&values("A")).unwrap();
+        let (a, b) = first.split_once(' ').unwrap();
+        assert_eq!(a, b);
+        assert!(uuid::Uuid::parse_str(a).is_ok());
+        assert_ne!(template.render(&values("A")).unwrap(), first);
+    }
+
+    #[test]
+    fn keeps_literal_braces() {
+        let template = Template::parse("tags: {}\ncode: {{title}} } { x }\n").unwrap();
+        assert_eq!(
+            template.render(&values("T")).unwrap(),
+            "tags: {}\ncode: {T} } { x }\n"
+        );
+    }
+
+    #[test]
+    fn keeps_yaml_flow_maps() {
+        let text = "---\nmeta: {a: 1, b: {c: 2}}\nref: {title: x}\n---\n# {title}\n";
+        let template = Template::parse(text).unwrap();
+        assert_eq!(
+            template.render(&values("T")).unwrap(),
+            "---\nmeta: {a: 1, b: {c: 2}}\nref: {title: x}\n---\n# T\n"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_and_invalid_placeholders() {
+        let err = |text: &str| format!("{:#}", Template::parse(text).unwrap_err());
+
+        assert_eq!(
+            err("# {title}\n\n{client}\n"),
+            "Invalid template on line 3: Unknown placeholder {client}"
+        );
+        assert!(err("{clinet:acme}").contains("Unknown placeholder {clinet:acme}"));
+        assert!(err("{env:}").contains("{env} needs a name"));
+        assert!(err("{date:%Q}").contains("Invalid date format in {date:%Q}"));
+        assert!(err("{env:}").contains("{env} needs a name"));
+        assert!(err("{title:x}").contains("{title} doesn't take an argument"));
+
+        let template = Template::parse("{prompt:Client}").unwrap();
+        let err = template.render(&values("T")).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "No value for {prompt:Client}; pass --var \"client=...\""
+        );
+    }
 }
@@ -3,9 +3,10 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
-use super::frontmatter;
-use super::markdown::{self, ParsedLink};
+use super::frontmatter::{self, FieldPosition};
+use super::markdown::{self, Heading, ParsedLink, Task};
 use super::query::{FieldValue, Queryable};
 
 /// Validation warning for a topic.
@@ -19,6 +20,55 @@ pub enum TopicWarning {
         line: usize,
         column: usize,
     },
+    /// Link to a heading that doesn't exist in the target topic.
+    BrokenAnchor {
+        target: String,
+        line: usize,
+        column: usize,
+    },
+    /// Wiki link whose bare target names more than one topic by file stem.
+    AmbiguousLink {
+        target: String,
+        /// Paths that would pick each match, relative to the linking topic.
+        candidates: Vec<String>,
+        line: usize,
+        column: usize,
+    },
+    /// Frontmatter block isn't valid YAML, so its fields are ignored.
+    InvalidFrontmatter {
+        message: String,
+        line: usize,
+        column: usize,
+    },
+    /// Schema field marked `required` is absent.
+    MissingField {
+        field: String,
+        line: usize,
+        column: usize,
+    },
+    /// Field value doesn't have the type declared in the schema.
+    InvalidFieldType {
+        field: String,
+        expected: String,
+        line: usize,
+        column: usize,
+    },
+    /// Field value isn't one of the schema's allowed values.
+    InvalidFieldValue {
+        field: String,
+        value: String,
+        allowed: Vec<String>,
+        line: usize,
+        column: usize,
+    },
+    /// No links to or from other topics (opt-in).
+    Orphan,
+    /// Linked to by other topics but links nowhere (opt-in).
+    DeadEnd,
+    /// Linked from many other topics (opt-in).
+    Hub {
+        linked_from: usize,
+    },
 }
 
 impl std::fmt::Display for TopicWarning {
@@ -33,6 +83,88 @@ impl std::fmt::Display for TopicWarning {
             } => {
                 write!(f, "Broken link: {} (line {}, col {})", target, line, column)
             }
+            TopicWarning::BrokenAnchor {
+                target,
+                line,
+                column,
+            } => {
+                write!(
+                    f,
+                    "Broken anchor: {} (line {}, col {})",
+                    target, line, column
+                )
+            }
+            TopicWarning::AmbiguousLink {
+                target,
+                candidates,
+                line,
+                column,
+            } => {
+                write!(
+                    f,
+                    "Ambiguous link: {} (matches {}) (line {}, col {})",
+                    target,
+                    candidates.join(", "),
+                    line,
+                    column
+                )
+            }
+            TopicWarning::InvalidFrontmatter {
+                message,
+                line,
+                column,
+            } => {
+                write!(
+                    f,
+                    "Invalid frontmatter: {} (line {}, col {})",
+                    message, line, column
+                )
+            }
+            TopicWarning::MissingField {
+                field,
+                line,
+                column,
+            } => {
+                write!(
+                    f,
+                    "Missing required field: {} (line {}, col {})",
+                    field, line, column
+                )
+            }
+            TopicWarning::InvalidFieldType {
+                field,
+                expected,
+                line,
+                column,
+            } => {
+                write!(
+                    f,
+                    "Invalid type for {}: expected {} (line {}, col {})",
+                    field, expected, line, column
+                )
+            }
+            TopicWarning::InvalidFieldValue {
+                field,
+                value,
+                allowed,
+                line,
+                column,
+            } => {
+                write!(
+                    f,
+                    "Invalid value for {}: {} (allowed: {}) (line {}, col {})",
+                    field,
+                    value,
+                    allowed.join(", "),
+                    line,
+                    column
+                )
+            }
+            TopicWarning::Orphan => write!(f, "Orphan (no links to or from other topics)"),
+            TopicWarning::DeadEnd => write!(f, "Dead end (no links to other topics)"),
+            TopicWarning::Hub { linked_from } => {
+                write!(f, "Hub (linked from {} topics)", linked_from)
+            }
         }
     }
 }
@@ -46,19 +178,42 @@ pub struct Topic {
     pub description: Option<String>,
     /// Links found in the document (with line numbers relative to file start).
     pub links: Vec<ParsedLink>,
+    /// Heading outline (with line numbers relative to file start).
+    #[serde(default)]
+    pub headings: Vec<Heading>,
+    /// Task list items (with line numbers relative to file start).
+    #[serde(default)]
+    pub tasks: Vec<Task>,
+    /// Markdown body (everything after the frontmatter), used for full-text search
+    #[serde(default)]
+    pub body: String,
     /// All frontmatter fields for queries
     #[serde(default)]
     pub metadata: HashMap<String, serde_yaml::Value>,
+    /// Line and column of each frontmatter field, for schema warnings
+    #[serde(default)]
+    pub field_positions: HashMap<String, FieldPosition>,
     /// Validation warnings
     #[serde(default)]
     pub warnings: Vec<TopicWarning>,
+    /// File modification time (if available)
+    #[serde(default)]
+    pub modified: Option<SystemTime>,
+    /// File creation time (if supported by the filesystem)
+    #[serde(default)]
+    pub created: Option<SystemTime>,
 }
 
 impl Topic {
     /// Parse a topic from a markdown file.
     pub fn from_path(path: &Path) -> Result<Self> {
         let content = fs::read_to_string(path).context("Failed to read file")?;
-        Ok(Self::from_content(path, &content))
+        let mut topic = Self::from_content(path, &content);
+        if let Ok(metadata) = fs::metadata(path) {
+            topic.modified = metadata.modified().ok();
+            topic.created = metadata.created().ok();
+        }
+        Ok(topic)
     }
 
     /// Parse a topic from content string (for testing).
@@ -67,6 +222,14 @@ impl Topic {
 
         let mut warnings = Vec::new();
 
+        if let Some(error) = &fm.error {
+            warnings.push(TopicWarning::InvalidFrontmatter {
+                message: error.message.clone(),
+                line: error.line,
+                column: error.column,
+            });
+        }
+
         // Check for empty content
         let body_trimmed = fm.body.trim();
         if body_trimmed.is_empty() {
@@ -85,6 +248,22 @@ impl Topic {
                 link
             })
             .collect();
+        let headings: Vec<Heading> = parsed
+            .headings
+            .into_iter()
+            .map(|mut heading| {
+                heading.line += fm.frontmatter_lines;
+                heading
+            })
+            .collect();
+        let tasks: Vec<Task> = parsed
+            .tasks
+            .into_iter()
+            .map(|mut task| {
+                task.line += fm.frontmatter_lines;
+                task
+            })
+            .collect();
 
         // Check for missing title
         let title = if let Some(t) = parsed.title {
@@ -102,8 +281,14 @@ impl Topic {
             title,
             description: parsed.description,
             links,
+            headings,
+            tasks,
+            body: fm.body,
             metadata: fm.metadata,
+            field_positions: fm.positions,
             warnings,
+            modified: None,
+            created: None,
         }
     }
 
@@ -112,6 +297,11 @@ impl Topic {
         frontmatter::parse_string_array(self.metadata.get("tags"))
     }
 
+    /// Alternative names from the `aliases` frontmatter field.
+    pub fn aliases(&self) -> Vec<String> {
+        frontmatter::parse_string_array(self.metadata.get("aliases"))
+    }
+
     /// Format for display.
     pub fn display(&self) -> TopicDisplay<'_> {
         TopicDisplay(self)
@@ -133,7 +323,7 @@ impl Queryable for Topic {
     }
 
     fn searchable_text(&self) -> String {
-        self.title.clone()
+        format!("{}\n{}", self.title, self.body)
     }
 }
 
@@ -214,6 +404,37 @@ mod tests {
         assert_eq!(topic.links[0].line, 8); // 5 frontmatter lines + 3 body lines
     }
 
+    #[test]
+    fn heading_line_offset_with_frontmatter() {
+        let content = "---\ntags:\n  - a\n---\n\n# Title\n\n## Details";
+        let topic = Topic::from_content(Path::new("test.md"), content);
+
+        let anchors: Vec<(&str, usize)> = topic
+            .headings
+            .iter()
+            .map(|h| (h.anchor.as_str(), h.line))
+            .collect();
+        assert_eq!(anchors, vec![("title", 6), ("details", 8)]);
+    }
+
+    #[test]
+    fn parse_invalid_frontmatter() {
+        let content = "---\nstatus: active\n  bad: x\n---\n\n# Title\n\n[link](other.md)";
+        let topic = Topic::from_content(Path::new("test.md"), content);
+
+        assert_eq!(topic.title, "Title");
+        assert!(topic.metadata.is_empty());
+        assert_eq!(topic.links[0].line, 8);
+        assert_eq!(
+            topic.warnings,
+            vec![TopicWarning::InvalidFrontmatter {
+                message: "mapping values are not allowed in this context".to_string(),
+                line: 3,
+                column: 6,
+            }]
+        );
+    }
+
     #[test]
     fn parse_with_description() {
         let content = "# Title\n\nThis is the description.\n\nMore content.";
@@ -226,6 +447,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn searchable_text_includes_body() {
+        let content = "---\nstatus: active\n---\n\n# Title\n\nMentions kubernetes.";
+        let topic = Topic::from_content(Path::new("test.md"), content);
+
+        let text = topic.searchable_text();
+        assert!(text.contains("Title"));
+        assert!(text.contains("kubernetes"));
+        assert!(!text.contains("status: active"));
+    }
+
     #[test]
     fn tags_convenience_method() {
         let content = "---\ntags:\n  - one\n  - two\n---\n\n# Title";
@@ -0,0 +1,196 @@
+//! Trash for deleted topics.
+//!
+//! `hypha delete` moves topics to `<root>/.hypha/trash/<id>/<path>`, where
+//! `id` is the time of deletion and `path` is the topic's path relative to
+//! the root, so they can be listed and restored where they were.
+
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDateTime};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::constants::{DATA_DIR, TRASH_DIR};
+
+/// Format of the deletion time at the start of each trash id.
+const ID_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// A topic in the trash.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trashed {
+    /// Trash entry the topic is stored under.
+    pub id: String,
+    /// Where the topic was, relative to the root.
+    pub original: PathBuf,
+    /// The file in the trash.
+    pub path: PathBuf,
+}
+
+impl Trashed {
+    /// When the topic was deleted.
+    pub fn deleted(&self) -> Option<NaiveDateTime> {
+        NaiveDateTime::parse_from_str(self.id.get(..15)?, ID_FORMAT).ok()
+    }
+}
+
+/// Sort key for a trash id: the deletion time, then the counter `slot` adds
+/// when several topics are deleted in the same second.
+fn order(id: &str) -> (Option<NaiveDateTime>, u32) {
+    let time = id
+        .get(..15)
+        .and_then(|time| NaiveDateTime::parse_from_str(time, ID_FORMAT).ok());
+    let counter = id
+        .get(15..)
+        .and_then(|rest| rest.strip_prefix('-'))
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(1);
+    (time, counter)
+}
+
+/// The trash directory for a root.
+pub fn dir(root: &Path) -> PathBuf {
+    root.join(DATA_DIR).join(TRASH_DIR)
+}
+
+/// Path in a new trash entry for the topic at `path`.
+pub fn slot(root: &Path, path: &Path) -> PathBuf {
+    let trash = dir(root);
+    let base = Local::now().format(ID_FORMAT).to_string();
+    let mut id = base.clone();
+    let mut n = 1;
+    while trash.join(&id).exists() {
+        n += 1;
+        id = format!("{}-{}", base, n);
+    }
+
+    let relative = path
+        .strip_prefix(root)
+        .ok()
+        .or_else(|| path.file_name().map(Path::new))
+        .unwrap_or(path);
+    trash.join(id).join(relative)
+}
+
+/// Topics in the trash, most recently deleted first.
+pub fn list(root: &Path) -> Result<Vec<Trashed>> {
+    let trash = dir(root);
+    if !trash.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut ids: Vec<PathBuf> = fs::read_dir(&trash)
+        .with_context(|| format!("Failed to read {}", trash.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    ids.sort_by_cached_key(|path| {
+        let id = path.file_name().unwrap_or_default().to_string_lossy();
+        std::cmp::Reverse(order(&id))
+    });
+
+    let mut trashed = Vec::new();
+    for entry in ids {
+        let id = entry
+            .file_name()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        for path in files(&entry)? {
+            trashed.push(Trashed {
+                id: id.clone(),
+                original: path.strip_prefix(&entry).unwrap_or(&path).to_path_buf(),
+                path,
+            });
+        }
+    }
+    Ok(trashed)
+}
+
+/// Remove `dir` and any parents up to the trash directory that are empty.
+pub fn prune(root: &Path, dir: &Path) {
+    let trash = self::dir(root);
+    let mut current = Some(dir);
+    while let Some(path) = current {
+        if path == trash || !path.starts_with(&trash) || fs::remove_dir(path).is_err() {
+            break;
+        }
+        current = path.parent();
+    }
+}
+
+/// Every file under `dir`, sorted.
+fn files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(self::files(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn lists_trashed_topics_newest_first() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let trash = dir(root);
+        fs::create_dir_all(trash.join("20260101-090000/projects")).unwrap();
+        fs::create_dir_all(trash.join("20260301-120000")).unwrap();
+        fs::write(trash.join("20260101-090000/projects/plan.md"), "").unwrap();
+        fs::write(trash.join("20260301-120000/notes.md"), "").unwrap();
+
+        let trashed = list(root).unwrap();
+        let originals: Vec<&Path> = trashed.iter().map(|t| t.original.as_path()).collect();
+        assert_eq!(
+            originals,
+            vec![Path::new("notes.md"), Path::new("projects/plan.md")]
+        );
+        assert_eq!(
+            trashed[1].deleted().unwrap().to_string(),
+            "2026-01-01 09:00:00"
+        );
+
+        let plan = trash.join("20260101-090000/projects/plan.md");
+        fs::remove_file(&plan).unwrap();
+        prune(root, plan.parent().unwrap());
+        assert!(!trash.join("20260101-090000").exists());
+        assert!(trash.exists());
+    }
+
+    #[test]
+    fn orders_same_second_ids_by_counter() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let trash = dir(root);
+        for id in ["20260301-120000", "20260301-120000-2", "20260301-120000-10"] {
+            fs::create_dir_all(trash.join(id)).unwrap();
+            fs::write(trash.join(id).join("plan.md"), "").unwrap();
+        }
+
+        let ids: Vec<String> = list(root).unwrap().into_iter().map(|t| t.id).collect();
+        assert_eq!(
+            ids,
+            vec!["20260301-120000-10", "20260301-120000-2", "20260301-120000"]
+        );
+    }
+
+    #[test]
+    fn slots_never_clash() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+
+        let first = slot(root, &root.join("projects/plan.md"));
+        assert!(first.ends_with("projects/plan.md"));
+        fs::create_dir_all(first.parent().unwrap()).unwrap();
+
+        let second = slot(root, &root.join("projects/plan.md"));
+        assert_ne!(first, second);
+    }
+}
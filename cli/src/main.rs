@@ -8,7 +8,7 @@ mod constants;
 mod core;
 mod utils;
 
-use cli::{Cli, Commands};
+use cli::{Cli, Commands, GraphCommand, TemplatesCommand, TrashCommand};
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
@@ -26,16 +26,125 @@ fn main() -> Result<()> {
         }
     };
     let root_override = cli.root.is_some();
+    let dry_run = cli.dry_run;
 
     match cli.command {
-        Commands::New { title, no_edit } => commands::new::run(&root, &title, !no_edit),
+        Commands::New {
+            title,
+            no_edit,
+            template,
+            vars,
+            fields,
+            dir,
+            body,
+            print_path,
+            json,
+        } => {
+            let options = commands::new::Options {
+                template: template.as_deref(),
+                vars: &vars,
+                fields: &fields,
+                dir: dir.as_deref(),
+                body: body.as_deref(),
+                open: !no_edit && !print_path && !json,
+                print_path,
+                json,
+            };
+            commands::new::run(&root, &title, &options, dry_run)
+        }
+        Commands::Today {
+            weekly,
+            monthly,
+            no_edit,
+            print_path,
+        } => commands::journal::run(
+            &root,
+            "today",
+            journal_period(weekly, monthly),
+            !no_edit && !print_path,
+            print_path,
+            dry_run,
+        ),
+        Commands::Journal {
+            date,
+            weekly,
+            monthly,
+            no_edit,
+            print_path,
+        } => commands::journal::run(
+            &root,
+            &date,
+            journal_period(weekly, monthly),
+            !no_edit && !print_path,
+            print_path,
+            dry_run,
+        ),
+        Commands::Tasks { query, json } => commands::tasks::run(&root, &query, json),
+        Commands::Templates {
+            command: TemplatesCommand::List { json },
+        } => commands::templates::list(&root, json),
         Commands::List { json, sort } => commands::list::run(&root, json, sort),
         Commands::Search { query, json, sort } => commands::search::run(&root, &query, json, sort),
         Commands::Open { topic } => commands::open::run(&root, &topic),
-        Commands::Delete { topic } => commands::delete::run(&root, &topic),
+        Commands::Delete { topic, yes } => commands::delete::run(&root, &topic, yes, dry_run),
+        Commands::Trash { command } => match command {
+            TrashCommand::List { json } => commands::trash::list(&root, json),
+            TrashCommand::Restore { item } => commands::trash::restore(&root, &item, dry_run),
+            TrashCommand::Empty { yes } => commands::trash::empty(&root, yes, dry_run),
+        },
         Commands::Lint { json } => commands::lint::run(&root, json),
         Commands::Info { verbose } => commands::info::run(&root, verbose, root_override),
-        Commands::Backlinks { topic, json } => commands::backlinks::run(&root, &topic, json),
-        Commands::Rename { from, to } => commands::rename::run(&root, &from, &to),
+        Commands::Backlinks {
+            topic,
+            json,
+            unlinked: true,
+            link,
+        } => commands::backlinks::unlinked(&root, &topic, json, link.as_deref(), dry_run),
+        Commands::Backlinks { topic, json, .. } => commands::backlinks::run(&root, &topic, json),
+        Commands::Rename { from, to } => commands::rename::run(&root, &from, &to, dry_run),
+        Commands::Move { args, filter } => {
+            commands::mv::run(&root, &args, filter.as_deref(), dry_run)
+        }
+        Commands::Graph {
+            command:
+                Some(GraphCommand::Stats {
+                    json,
+                    hub_threshold,
+                }),
+            ..
+        } => commands::graph::stats(&root, json, hub_threshold),
+        Commands::Graph {
+            command: None,
+            format,
+            filter,
+            around,
+            depth,
+            fields,
+        } => commands::graph::run(
+            &root,
+            format,
+            &commands::graph::Selection {
+                filter: filter.as_deref(),
+                around: around.as_deref(),
+                depth,
+                fields: &fields,
+            },
+        ),
+        Commands::Set { args, filter } => {
+            commands::set::run(&root, &args, filter.as_deref(), dry_run)
+        }
+        Commands::Undo => commands::undo::run(&root, dry_run),
+        Commands::Unset { args, filter } => {
+            commands::unset::run(&root, &args, filter.as_deref(), dry_run)
+        }
+    }
+}
+
+fn journal_period(weekly: bool, monthly: bool) -> commands::journal::Period {
+    use commands::journal::Period;
+    match (weekly, monthly) {
+        (true, _) => Period::Weekly,
+        (_, true) => Period::Monthly,
+        _ => Period::Daily,
     }
 }
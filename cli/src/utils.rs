@@ -1,3 +1,56 @@
+use anyhow::{bail, Context, Result};
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Component, Path};
+
+/// Whether a relative path stays inside the directory it's joined to: it
+/// has no `..`, root or drive components.
+pub fn is_contained(path: &Path) -> bool {
+    path.components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// Ask a yes/no question on the terminal. Anything but "y" or "yes" is no.
+///
+/// Fails if stdin isn't a terminal, so scripts have to pass `--yes` instead.
+pub fn confirm(question: &str) -> Result<bool> {
+    if !io::stdin().is_terminal() {
+        bail!("Can't ask for confirmation without a terminal; pass --yes to continue");
+    }
+    print!("{} [y/N] ", question);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(matches!(input.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Ask for a line of text on the terminal, without its trailing newline.
+///
+/// Fails if stdin isn't a terminal.
+pub fn ask(question: &str) -> Result<String> {
+    if !io::stdin().is_terminal() {
+        bail!("Can't ask \"{}\" without a terminal", question);
+    }
+    print!("{}: ", question);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Read all of stdin, which must be piped. `what` names the option reading it.
+pub fn read_stdin(what: &str) -> Result<String> {
+    if io::stdin().is_terminal() {
+        bail!("{} reads standard input, but nothing was piped to it", what);
+    }
+    let mut input = String::new();
+    io::stdin()
+        .read_to_string(&mut input)
+        .context("Failed to read standard input")?;
+    Ok(input)
+}
+
 /// Convert a string into a URL/filename-safe slug.
 ///
 /// - Lowercases the input
@@ -26,6 +79,15 @@ pub fn slugify(title: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn contained_paths() {
+        assert!(is_contained(Path::new("projects/2026")));
+        assert!(is_contained(Path::new("./notes")));
+        assert!(!is_contained(Path::new("../elsewhere")));
+        assert!(!is_contained(Path::new("notes/../../elsewhere")));
+        assert!(!is_contained(Path::new("/tmp")));
+    }
+
     #[test]
     fn slugify_basic() {
         assert_eq!(slugify("My Great Topic"), "my-great-topic");
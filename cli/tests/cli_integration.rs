@@ -10,6 +10,17 @@ fn fixtures_path() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
 }
 
+/// Create a temporary root holding `files`, given as `(path, content)` pairs.
+fn vault(files: &[(&str, &str)]) -> tempfile::TempDir {
+    let temp = tempfile::TempDir::new().unwrap();
+    for (path, content) in files {
+        let path = temp.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+    temp
+}
+
 /// Run the hypha CLI with the given arguments.
 fn run_hypha(args: &[&str]) -> (String, String, bool) {
     let binary = env!("CARGO_BIN_EXE_hypha");
@@ -104,6 +115,103 @@ mod backlinks {
         assert_eq!(arr.len(), 2, "Should have 2 backlinks in JSON");
     }
 
+    #[test]
+    fn backlinks_report_every_link_with_context() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp.path().join("target.md"), "# Target\n").unwrap();
+        std::fs::write(
+            temp.path().join("source.md"),
+            "# Source\n\nSee [the target](target.md)\nfor details.\n\n- Also [[target|again]]\n",
+        )
+        .unwrap();
+
+        let (stdout, stderr, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "backlinks",
+            "target",
+            "--json",
+        ]);
+        assert!(success, "{}", stderr);
+
+        let parsed: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        assert_eq!(
+            parsed[0]["links"],
+            serde_json::json!([
+                {
+                    "line": 3,
+                    "column": 5,
+                    "text": "the target",
+                    "context": "See the target for details."
+                },
+                {
+                    "line": 6,
+                    "column": 8,
+                    "text": "again",
+                    "context": "Also again"
+                }
+            ])
+        );
+
+        let (stdout, _, _) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "backlinks",
+            "target",
+        ]);
+        assert!(stdout.contains("    3:5 the target\n      See the target for details.\n"));
+        assert!(stdout.contains("1 backlink(s) found (2 link(s))"));
+    }
+
+    #[test]
+    fn backlinks_resolve_paths_across_directories() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("projects")).unwrap();
+        std::fs::create_dir_all(root.join("areas")).unwrap();
+        std::fs::write(root.join("projects/index.md"), "# Projects\n").unwrap();
+        std::fs::write(root.join("areas/index.md"), "# Areas\n").unwrap();
+        std::fs::write(
+            root.join("projects/plan.md"),
+            "# Plan\n\nSee [projects](index.md) and [areas](../areas/index.md).\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("notes.md"), "# Notes\n\nSee [[index]].\n").unwrap();
+        let root = root.to_str().unwrap();
+
+        let backlinks = |topic: &str| -> Vec<(String, usize)> {
+            let (stdout, stderr, success) =
+                run_hypha(&["--root", root, "backlinks", topic, "--json"]);
+            assert!(success, "{}", stderr);
+            let parsed: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+            parsed
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|b| {
+                    (
+                        b["title"].as_str().unwrap().to_string(),
+                        b["links"].as_array().unwrap().len(),
+                    )
+                })
+                .collect()
+        };
+        assert_eq!(backlinks("projects/index"), vec![("Plan".to_string(), 1)]);
+        assert_eq!(
+            backlinks("areas/index.md"),
+            vec![("Notes".to_string(), 1), ("Plan".to_string(), 1)]
+        );
+
+        let (_, stderr, success) = run_hypha(&["--root", root, "backlinks", "index"]);
+        assert!(!success);
+        assert!(stderr.contains("Multiple topics match: index"));
+
+        let (stdout, _, _) = run_hypha(&["--root", root, "lint"]);
+        assert!(stdout.contains(
+            "Ambiguous link: index (matches areas/index, projects/index) (line 3, col 5)"
+        ));
+    }
+
     #[test]
     fn backlinks_not_found_topic() {
         let fixtures = fixtures_path();
@@ -119,6 +227,160 @@ mod backlinks {
     }
 }
 
+mod unlinked_mentions {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup() -> TempDir {
+        vault(&[
+            (
+                "project-plan.md",
+                "---\naliases: [the roadmap]\n---\n\n# Project Plan\n",
+            ),
+            (
+                "notes.md",
+                "# Notes\n\nThe project plan and [Project Plan](project-plan.md).\n\nSee the Roadmap.\n",
+            ),
+            ("sub/deep.md", "# Deep\n\nAbout the project plan.\n"),
+        ])
+    }
+
+    #[test]
+    fn lists_mentions_as_json() {
+        let temp = setup();
+        let (stdout, stderr, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "backlinks",
+            "Project Plan",
+            "--unlinked",
+            "--json",
+        ]);
+        assert!(success, "{}", stderr);
+
+        let parsed: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        let mentions: Vec<(String, u64, u64, String)> = parsed
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| {
+                (
+                    m["text"].as_str().unwrap().to_string(),
+                    m["line"].as_u64().unwrap(),
+                    m["column"].as_u64().unwrap(),
+                    m["snippet"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            mentions,
+            vec![
+                (
+                    "project plan".to_string(),
+                    3,
+                    11,
+                    "About the project plan.".to_string()
+                ),
+                (
+                    "project plan".to_string(),
+                    3,
+                    5,
+                    "The project plan and [Project Plan](project-pl…".to_string()
+                ),
+                (
+                    "the Roadmap".to_string(),
+                    5,
+                    5,
+                    "See the Roadmap.".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn links_chosen_mentions() {
+        let temp = setup();
+        let (stdout, stderr, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "backlinks",
+            "project-plan",
+            "--unlinked",
+            "--link=1,3",
+        ]);
+        assert!(success, "{}", stderr);
+        assert!(stdout.contains("Linked 1 mention(s)"));
+
+        let deep = fs::read_to_string(temp.path().join("sub/deep.md")).unwrap();
+        assert_eq!(
+            deep,
+            "# Deep\n\nAbout the [project plan](../project-plan.md).\n"
+        );
+        let notes = fs::read_to_string(temp.path().join("notes.md")).unwrap();
+        assert_eq!(
+            notes,
+            "# Notes\n\nThe project plan and [Project Plan](project-plan.md).\n\nSee [the Roadmap](project-plan.md).\n"
+        );
+
+        let (_, stderr, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "backlinks",
+            "project-plan",
+            "--unlinked",
+            "--link=2",
+        ]);
+        assert!(!success);
+        assert!(stderr.contains("No unlinked mention 2 (found 1)"));
+    }
+}
+
+mod wiki_links {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> TempDir {
+        vault(&[
+            ("target.md", "# Target Topic\n\n## Body\n\nBody."),
+            ("projects/by-stem.md", "# By Stem\n\nSee [[target]]."),
+            (
+                "by-title.md",
+                "# By Title\n\nSee [[Target Topic#Body|the target]].",
+            ),
+            ("broken.md", "# Broken\n\nSee [[nowhere]]."),
+        ])
+    }
+
+    #[test]
+    fn backlinks_include_wiki_links() {
+        let temp = setup();
+        let (stdout, _, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "backlinks",
+            "target",
+        ]);
+
+        assert!(success);
+        assert!(stdout.contains("By Stem"));
+        assert!(stdout.contains("By Title"));
+        assert!(stdout.contains("2 backlink(s) found"));
+    }
+
+    #[test]
+    fn lint_reports_broken_wiki_links() {
+        let temp = setup();
+        let (stdout, _, success) = run_hypha(&["--root", temp.path().to_str().unwrap(), "lint"]);
+
+        assert!(!success);
+        assert!(stdout.contains("Broken link: nowhere"));
+        assert!(!stdout.contains("Broken link: target"));
+        assert!(!stdout.contains("Broken link: Target Topic"));
+        assert!(!stdout.contains("Broken anchor"));
+    }
+}
+
 mod lint {
     use super::*;
 
@@ -182,6 +444,158 @@ mod lint {
             "Warning should have column number"
         );
     }
+
+    #[test]
+    fn reports_invalid_frontmatter() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("typo.md"),
+            "---\nstatus: active\ntags: [a, b\n---\n\n# Typo\n\nBody.\n",
+        )
+        .unwrap();
+        let root = temp.path().to_str().unwrap();
+
+        let (stdout, _, success) = run_hypha(&["--root", root, "lint"]);
+        assert!(!success);
+        assert!(stdout.contains("Invalid frontmatter: did not find expected ',' or ']'"));
+
+        let (stdout, _, _) = run_hypha(&["--root", root, "lint", "--json"]);
+        let parsed: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        let warning = &parsed[0]["warnings"][0];
+        assert_eq!(warning["severity"], "error");
+        assert_eq!(warning["line"], 4);
+        assert_eq!(warning["column"], 1);
+
+        // The topic keeps its title rather than showing the raw frontmatter
+        let (stdout, _, _) = run_hypha(&["--root", root, "list"]);
+        assert!(stdout.contains("Typo"));
+        assert!(!stdout.contains("---"));
+    }
+
+    #[test]
+    fn reports_schema_violations() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join(".hypha")).unwrap();
+        std::fs::write(
+            temp.path().join(".hypha/schema.toml"),
+            "[fields.status]\ntype = \"enum\"\nvalues = [\"active\", \"done\"]\nrequired = true\n\n[fields.due]\ntype = \"date\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp.path().join("valid.md"),
+            "---\nstatus: done\ndue: 2026-03-01\n---\n\n# Valid\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp.path().join("invalid.md"),
+            "---\nstatus: wip\ndue: soon\n---\n\n# Invalid\n",
+        )
+        .unwrap();
+        std::fs::write(temp.path().join("missing.md"), "# Missing\n").unwrap();
+
+        let (stdout, _, success) =
+            run_hypha(&["--root", temp.path().to_str().unwrap(), "lint", "--json"]);
+        assert!(!success);
+
+        let parsed: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        let warnings = |file: &str| -> Vec<(String, u64, u64)> {
+            let entry = parsed
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|v| v["path"].as_str().unwrap().ends_with(file))
+                .unwrap_or_else(|| panic!("{} should have warnings", file));
+            entry["warnings"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|w| {
+                    (
+                        w["message"].as_str().unwrap().to_string(),
+                        w["line"].as_u64().unwrap(),
+                        w["column"].as_u64().unwrap(),
+                    )
+                })
+                .collect()
+        };
+
+        assert_eq!(parsed.as_array().unwrap().len(), 2);
+        assert_eq!(
+            warnings("invalid.md"),
+            vec![
+                ("Invalid type for due: expected date".to_string(), 3, 6),
+                (
+                    "Invalid value for status: wip (allowed: active, done)".to_string(),
+                    2,
+                    9
+                ),
+            ]
+        );
+        assert_eq!(
+            warnings("missing.md"),
+            vec![("Missing required field: status".to_string(), 1, 1)]
+        );
+    }
+
+    #[test]
+    fn reports_graph_rules_when_enabled() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp.path().join("a.md"), "# A\n\n[B](b.md)\n").unwrap();
+        std::fs::write(temp.path().join("b.md"), "# B\n").unwrap();
+        std::fs::write(temp.path().join("c.md"), "# C\n").unwrap();
+        let root = temp.path().to_str().unwrap();
+
+        let (stdout, _, success) = run_hypha(&["--root", root, "lint"]);
+        assert!(success, "graph rules are off by default: {}", stdout);
+
+        std::fs::create_dir_all(temp.path().join(".hypha")).unwrap();
+        std::fs::write(
+            temp.path().join(".hypha/config.toml"),
+            "[lint]\norphans = true\ndead_ends = true\nhubs = true\nhub_threshold = 1\n",
+        )
+        .unwrap();
+
+        let (stdout, _, success) = run_hypha(&["--root", root, "lint"]);
+        assert!(!success);
+        assert!(!stdout.contains("a.md:"));
+        assert!(stdout.contains("Dead end (no links to other topics)"));
+        assert!(stdout.contains("Hub (linked from 1 topics)"));
+        assert!(stdout.contains("Orphan (no links to or from other topics)"));
+    }
+
+    #[test]
+    fn reports_broken_anchors() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("target.md"),
+            "# Target\n\n## Setup Steps\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp.path().join("source.md"),
+            "# Source\n\n## Notes\n\n[ok](target.md#setup-steps) [bad](target.md#install)\n\n[self](#notes) [gone](#missing)\n",
+        )
+        .unwrap();
+
+        let (stdout, _, success) =
+            run_hypha(&["--root", temp.path().to_str().unwrap(), "lint", "--json"]);
+        assert!(!success);
+
+        let parsed: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        let messages: Vec<&str> = parsed[0]["warnings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|w| w["message"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Broken anchor: target.md#install",
+                "Broken anchor: #missing"
+            ]
+        );
+    }
 }
 
 mod list {
@@ -282,6 +696,114 @@ mod list {
     }
 }
 
+mod cache {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn list_reflects_changes_after_caching() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().to_str().unwrap();
+        fs::write(temp.path().join("a.md"), "# Alpha").unwrap();
+        fs::write(temp.path().join("b.md"), "# Beta").unwrap();
+
+        let (stdout, _, success) = run_hypha(&["--root", root, "list"]);
+        assert!(success);
+        assert!(stdout.contains("Alpha") && stdout.contains("Beta"));
+        assert!(temp.path().join(".hypha/index.json").exists());
+
+        fs::write(temp.path().join("a.md"), "# Alpha Renamed").unwrap();
+        fs::remove_file(temp.path().join("b.md")).unwrap();
+        fs::write(temp.path().join("c.md"), "# Gamma").unwrap();
+
+        let (stdout, _, success) = run_hypha(&["--root", root, "list"]);
+        assert!(success);
+        assert!(stdout.contains("Alpha Renamed"));
+        assert!(!stdout.contains("Beta"));
+        assert!(stdout.contains("Gamma"));
+    }
+}
+
+mod ignore_files {
+    use super::*;
+
+    fn titles(root: &std::path::Path) -> Vec<String> {
+        let (stdout, stderr, success) =
+            run_hypha(&["--root", root.to_str().unwrap(), "list", "--json"]);
+        assert!(success, "list failed: {}", stderr);
+        let parsed: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        parsed
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v["title"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn honours_gitignore_and_hyphaignore() {
+        let temp = vault(&[
+            ("notes/kept.md", "# Kept"),
+            ("node_modules/pkg/README.md", "# Package Readme"),
+            ("vendor/docs.md", "# Vendored Docs"),
+            (".git/info.md", "# Git Internals"),
+            (".gitignore", "node_modules/\n"),
+            (".hyphaignore", "vendor/\n"),
+        ]);
+        let root = temp.path();
+
+        assert_eq!(titles(root), vec!["Kept"]);
+    }
+
+    #[test]
+    fn honours_configured_globs() {
+        let temp = vault(&[
+            ("notes/kept.md", "# Kept"),
+            ("notes/old.draft.md", "# Draft"),
+            ("archive/old.md", "# Archived"),
+            ("outside.md", "# Outside"),
+            (
+                ".hypha/config.toml",
+                "[index]\ninclude = [\"notes/**\", \"archive/**\"]\nexclude = [\"archive/\", \"*.draft.md\"]\n",
+            ),
+        ]);
+        let root = temp.path();
+
+        assert_eq!(titles(root), vec!["Kept"]);
+    }
+
+    #[test]
+    fn invalid_settings_fail() {
+        let temp = vault(&[
+            ("a.md", "# A"),
+            (".hypha/config.toml", "[index]\nexclude = \"not-a-list\"\n"),
+        ]);
+        let root = temp.path();
+
+        let (_, stderr, success) = run_hypha(&["--root", root.to_str().unwrap(), "list"]);
+        assert!(!success);
+        assert!(stderr.contains("Failed to parse settings file"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn survives_symlink_loops() {
+        let temp = vault(&[("notes/a.md", "# A")]);
+        let root = temp.path();
+        std::os::unix::fs::symlink(root, root.join("notes/loop")).unwrap();
+        std::os::unix::fs::symlink(root.join("notes"), root.join("alias")).unwrap();
+
+        assert_eq!(titles(root), vec!["A"]);
+        let (_, stderr, _) = run_hypha(&["--root", root.to_str().unwrap(), "list"]);
+        assert!(
+            stderr.contains("Warning: File system loop found"),
+            "{}",
+            stderr
+        );
+    }
+}
+
 mod search {
     use super::*;
 
@@ -307,6 +829,68 @@ mod search {
         assert!(!stdout.contains("Topic A"));
     }
 
+    #[test]
+    fn search_with_or_and_negation() {
+        let fixtures = fixtures_path();
+        let (stdout, _, success) = run_hypha(&[
+            "--root",
+            fixtures.to_str().unwrap(),
+            "search",
+            "(tag:orphan OR tag:example) -title:\"topic c\"",
+        ]);
+
+        assert!(success);
+        assert!(stdout.contains("Topic A"));
+        assert!(!stdout.contains("Topic C"));
+        assert!(!stdout.contains("Topic B"));
+    }
+
+    #[test]
+    fn search_invalid_query_fails() {
+        let fixtures = fixtures_path();
+        let (_, stderr, success) = run_hypha(&[
+            "--root",
+            fixtures.to_str().unwrap(),
+            "search",
+            "(tag:orphan",
+        ]);
+
+        assert!(!success);
+        assert!(stderr.contains("Missing closing ')'"));
+    }
+
+    #[test]
+    fn search_matches_body_text() {
+        let fixtures = fixtures_path();
+        let (stdout, _, success) =
+            run_hypha(&["--root", fixtures.to_str().unwrap(), "search", "second"]);
+
+        assert!(success);
+        assert!(stdout.contains("Topic B"));
+        assert!(!stdout.contains("Topic A"));
+    }
+
+    #[test]
+    fn search_sort_relevance_ranks_title_first() {
+        let fixtures = fixtures_path();
+        let (stdout, _, success) = run_hypha(&[
+            "--root",
+            fixtures.to_str().unwrap(),
+            "search",
+            "links",
+            "--sort",
+            "relevance",
+            "--json",
+        ]);
+
+        assert!(success);
+        let parsed: serde_json::Value =
+            serde_json::from_str(&stdout).expect("Should be valid JSON");
+        let arr = parsed.as_array().unwrap();
+        assert!(arr.len() > 1, "Body mentions should also match");
+        assert_eq!(arr[0]["title"], "Broken Links");
+    }
+
     #[test]
     fn search_no_results() {
         let fixtures = fixtures_path();
@@ -400,6 +984,7 @@ mod new_and_delete {
             temp.path().to_str().unwrap(),
             "delete",
             "to-delete",
+            "--yes",
         ]);
 
         assert!(success, "Should delete topic");
@@ -407,6 +992,60 @@ mod new_and_delete {
         assert!(!topic_file.exists(), "File should be deleted");
     }
 
+    #[test]
+    fn delete_never_picks_a_body_match() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().to_str().unwrap();
+        let mention = temp.path().join("alpha.md");
+        fs::write(&mention, "# Alpha\n\nDeploys to Kubernetes.").unwrap();
+
+        // Only the body of alpha.md mentions the query
+        let (_, stderr, success) = run_hypha(&["--root", root, "delete", "kubernetes", "--yes"]);
+        assert!(!success);
+        assert!(stderr.contains("No topic found"), "got: {}", stderr);
+        assert!(mention.exists(), "A body match must not be deleted");
+
+        // A title shared with another note's body deletes by path
+        let topic = temp.path().join("kubernetes.md");
+        fs::write(&topic, "# Kubernetes\n").unwrap();
+        let (_, _, success) =
+            run_hypha(&["--root", root, "delete", topic.to_str().unwrap(), "--yes"]);
+        assert!(success);
+        assert!(!topic.exists());
+        assert!(mention.exists(), "Only the named file should be deleted");
+    }
+
+    #[test]
+    fn delete_accepts_queries_matched_on_titles() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().to_str().unwrap();
+        let mention = temp.path().join("alpha.md");
+        fs::write(
+            &mention,
+            "---\nstatus: active\n---\n# Alpha\n\nDeploys to Kubernetes.",
+        )
+        .unwrap();
+        let topic = temp.path().join("kubernetes.md");
+        fs::write(&topic, "---\nstatus: active\n---\n# Kubernetes\n").unwrap();
+        fs::write(temp.path().join("beta.md"), "# Beta\n").unwrap();
+
+        // Both active topics mention the term, but only one has it in its title
+        let (_, stderr, success) = run_hypha(&[
+            "--root",
+            root,
+            "delete",
+            "status:active kubernetes",
+            "--yes",
+        ]);
+        assert!(success, "{}", stderr);
+        assert!(!topic.exists());
+        assert!(mention.exists());
+
+        let (_, stderr, success) = run_hypha(&["--root", root, "delete", "status:active", "--yes"]);
+        assert!(success, "{}", stderr);
+        assert!(!mention.exists());
+    }
+
     #[test]
     fn delete_nonexistent_fails() {
         let temp = TempDir::new().unwrap();
@@ -432,23 +1071,614 @@ mod new_and_delete {
     }
 }
 
-mod rename {
+mod templates {
     use super::*;
     use std::fs;
     use tempfile::TempDir;
 
-    #[test]
-    fn renames_topic() {
-        let temp = TempDir::new().unwrap();
+    fn setup() -> TempDir {
+        vault(&[
+            (
+                ".hypha/templates/meeting.md",
+                "# {title}\n\n## Attendees\n\n## Actions\n",
+            ),
+            (".hypha/templates/adr.md", "# ADR: {title}\n"),
+            (
+                ".hypha/config.toml",
+                "[templates.meeting]\nfolder = \"meetings\"\ndescription = \"Meeting notes\"\n",
+            ),
+        ])
+    }
 
-        // Create a topic to rename
-        let original = temp.path().join("original.md");
-        fs::write(&original, "# Original Title\n\nSome content.").unwrap();
+    #[test]
+    fn new_uses_named_template_and_folder() {
+        let temp = setup();
 
-        let (_, _, success) = run_hypha(&[
+        let (stdout, stderr, success) = run_hypha(&[
             "--root",
             temp.path().to_str().unwrap(),
-            "rename",
+            "new",
+            "--template",
+            "meeting",
+            "Weekly Sync",
+            "--no-edit",
+        ]);
+        assert!(success, "stderr: {}", stderr);
+        assert!(stdout.contains("Created"));
+
+        let content = fs::read_to_string(temp.path().join("meetings/weekly-sync.md")).unwrap();
+        assert_eq!(content, "# Weekly Sync\n\n## Attendees\n\n## Actions\n");
+
+        // Templates without a folder create topics in the root
+        let (_, _, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "new",
+            "-t",
+            "adr",
+            "Use SQLite",
+            "--no-edit",
+        ]);
+        assert!(success);
+        assert_eq!(
+            fs::read_to_string(temp.path().join("use-sqlite.md")).unwrap(),
+            "# ADR: Use SQLite\n"
+        );
+    }
+
+    #[test]
+    fn unknown_template_lists_available() {
+        let temp = setup();
+
+        let (_, stderr, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "new",
+            "--template",
+            "missing",
+            "Title",
+            "--no-edit",
+        ]);
+        assert!(!success);
+        assert!(
+            stderr.contains("Unknown template: missing (available: adr, meeting)"),
+            "stderr: {}",
+            stderr
+        );
+        assert!(!temp.path().join("title.md").exists());
+    }
+
+    #[test]
+    fn lists_templates() {
+        let temp = setup();
+
+        let (stdout, _, success) =
+            run_hypha(&["--root", temp.path().to_str().unwrap(), "templates", "list"]);
+        assert!(success);
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(
+            lines,
+            vec!["  adr", "  meeting  Meeting notes  (in meetings/)"]
+        );
+
+        let (stdout, _, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "templates",
+            "list",
+            "--json",
+        ]);
+        assert!(success);
+        let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        assert_eq!(json[1]["name"], "meeting");
+        assert_eq!(json[1]["folder"], "meetings");
+        assert_eq!(json[1]["prompts"], serde_json::json!([]));
+        assert!(json[0]["folder"].is_null());
+    }
+}
+
+mod template_variables {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+    use std::process::Stdio;
+    use tempfile::TempDir;
+
+    fn setup(template: &str) -> TempDir {
+        vault(&[(".hypha/templates/note.md", template)])
+    }
+
+    #[test]
+    fn fills_prompts_from_vars() {
+        let temp = setup("# {title}\n\nslug: {slug}\nclient: {prompt:Client}\n");
+
+        let (_, stderr, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "new",
+            "-t",
+            "note",
+            "Kickoff Call",
+            "--var",
+            "client=Acme",
+            "--no-edit",
+        ]);
+        assert!(success, "stderr: {}", stderr);
+        assert_eq!(
+            fs::read_to_string(temp.path().join("kickoff-call.md")).unwrap(),
+            "# Kickoff Call\n\nslug: kickoff-call\nclient: Acme\n"
+        );
+    }
+
+    #[test]
+    fn missing_prompt_without_terminal_fails() {
+        let temp = setup("client: {prompt:Client}\n");
+
+        let (_, stderr, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "new",
+            "-t",
+            "note",
+            "Kickoff",
+            "--no-edit",
+        ]);
+        assert!(!success);
+        assert!(
+            stderr.contains("The template asks for Client; pass --var \"client=...\""),
+            "stderr: {}",
+            stderr
+        );
+        assert!(!temp.path().join("kickoff.md").exists());
+    }
+
+    #[test]
+    fn unknown_placeholder_fails() {
+        let temp = setup("# {title}\n{clinet}\n");
+
+        let (_, stderr, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "new",
+            "-t",
+            "note",
+            "Kickoff",
+            "--no-edit",
+        ]);
+        assert!(!success);
+        assert!(
+            stderr.contains("Unknown placeholder {clinet}"),
+            "stderr: {}",
+            stderr
+        );
+        assert!(!temp.path().join("kickoff.md").exists());
+    }
+
+    #[test]
+    fn keeps_yaml_flow_maps() {
+        let temp = setup("---\nmeta: {a: 1}\n---\n# {title}\n");
+
+        let (_, stderr, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "new",
+            "-t",
+            "note",
+            "Kickoff",
+            "--no-edit",
+        ]);
+        assert!(success, "stderr: {}", stderr);
+        assert_eq!(
+            fs::read_to_string(temp.path().join("kickoff.md")).unwrap(),
+            "---\nmeta: {a: 1}\n---\n# Kickoff\n"
+        );
+    }
+
+    #[test]
+    fn reads_stdin() {
+        let temp = setup("# {title}\n\n{stdin}\n");
+
+        let mut child = Command::new(env!("CARGO_BIN_EXE_hypha"))
+            .args([
+                "--root",
+                temp.path().to_str().unwrap(),
+                "new",
+                "-t",
+                "note",
+                "Pasted",
+                "--no-edit",
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(b"Some copied text\n")
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+
+        assert_eq!(
+            fs::read_to_string(temp.path().join("pasted.md")).unwrap(),
+            "# Pasted\n\nSome copied text\n"
+        );
+    }
+}
+
+mod new_options {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+    use std::process::Stdio;
+    use tempfile::TempDir;
+
+    #[test]
+    fn sets_fields_dir_and_body() {
+        let temp = TempDir::new().unwrap();
+
+        let (stdout, stderr, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "new",
+            "Launch Plan",
+            "--field",
+            "status=active",
+            "--field",
+            "tags=[a, b]",
+            "--dir",
+            "projects/acme",
+            "--body",
+            "First steps.",
+            "--json",
+        ]);
+        assert!(success, "stderr: {}", stderr);
+
+        let path = temp.path().join("projects/acme/launch-plan.md");
+        let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        assert_eq!(json["title"], "Launch Plan");
+        assert_eq!(json["path"], path.to_str().unwrap());
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("---\nstatus: active\ntags: [a, b]\n---\n"));
+        assert!(content.contains("# Launch Plan"));
+        assert!(content.ends_with("\n\nFirst steps.\n"), "{}", content);
+    }
+
+    #[test]
+    fn reads_body_from_stdin_and_prints_path() {
+        let temp = TempDir::new().unwrap();
+
+        let mut child = Command::new(env!("CARGO_BIN_EXE_hypha"))
+            .args([
+                "--root",
+                temp.path().to_str().unwrap(),
+                "new",
+                "Notes",
+                "--body",
+                "-",
+                "--print-path",
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(b"Piped body\n")
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+
+        let path = temp.path().join("notes.md");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            path.to_str().unwrap()
+        );
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .ends_with("\n\nPiped body\n"));
+    }
+
+    #[test]
+    fn rejects_dir_outside_root() {
+        let temp = TempDir::new().unwrap();
+
+        let (_, stderr, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "new",
+            "Escape",
+            "--dir",
+            "../elsewhere",
+            "--no-edit",
+        ]);
+        assert!(!success);
+        assert!(stderr.contains("Directory must be inside the root"));
+    }
+}
+
+mod journal {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn creates_then_finds_daily_note() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().to_str().unwrap();
+
+        let (stdout, stderr, success) =
+            run_hypha(&["--root", root, "journal", "2026-03-18", "--no-edit"]);
+        assert!(success, "stderr: {}", stderr);
+        assert!(stdout.contains("Created"));
+
+        let path = temp.path().join("journal/2026-03-18.md");
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "---\ndate: 2026-03-18\n---\n\n# Wednesday, 18 March 2026\n\n"
+        );
+
+        let (stdout, _, success) =
+            run_hypha(&["--root", root, "journal", "2026-03-18", "--print-path"]);
+        assert!(success);
+        assert_eq!(stdout.trim(), path.to_str().unwrap());
+
+        // Journal notes can be found by date
+        let (stdout, _, _) = run_hypha(&["--root", root, "search", "date:2026-03-18"]);
+        assert!(stdout.contains("Wednesday, 18 March 2026"), "{}", stdout);
+    }
+
+    #[test]
+    fn uses_configured_paths_and_templates() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().to_str().unwrap();
+        fs::create_dir_all(temp.path().join(".hypha/templates")).unwrap();
+        fs::write(
+            temp.path().join(".hypha/templates/review.md"),
+            "# {title}\n\nWeek of {date:%-d %B}\n",
+        )
+        .unwrap();
+        fs::write(
+            temp.path().join(".hypha/config.toml"),
+            "[journal]\nfolder = \"log\"\n\n[journal.weekly]\nfilename = \"%Y/week-%V\"\ntemplate = \"review\"\n",
+        )
+        .unwrap();
+
+        let (_, stderr, success) = run_hypha(&[
+            "--root",
+            root,
+            "journal",
+            "2026-03-18",
+            "--weekly",
+            "--no-edit",
+        ]);
+        assert!(success, "stderr: {}", stderr);
+
+        assert_eq!(
+            fs::read_to_string(temp.path().join("log/2026/week-12.md")).unwrap(),
+            "---\ndate: 2026-03-16\n---\n\n# Week 12, 2026\n\nWeek of 16 March\n"
+        );
+    }
+
+    #[test]
+    fn today_creates_monthly_note() {
+        let temp = TempDir::new().unwrap();
+
+        let (stdout, stderr, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "today",
+            "--monthly",
+            "--print-path",
+        ]);
+        assert!(success, "stderr: {}", stderr);
+
+        let path = PathBuf::from(stdout.trim());
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        assert_eq!(name.len(), "2026-03.md".len(), "{}", name);
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("-01\n---"), "{}", content);
+    }
+}
+
+mod tasks {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> TempDir {
+        vault(&[
+            (
+                "launch.md",
+                "---\nstatus: active\n---\n\n# Launch\n\n\
+                 - [ ] Write spec due:2026-11-01 @sam\n\
+                 - [x] Book room @alex\n",
+            ),
+            (
+                "ideas.md",
+                "# Ideas\n\n- [ ] Try a thing due:2027-01-01\n- Not a task\n",
+            ),
+        ])
+    }
+
+    #[test]
+    fn lists_tasks_by_topic() {
+        let temp = setup();
+
+        let (stdout, _, success) = run_hypha(&["--root", temp.path().to_str().unwrap(), "tasks"]);
+        assert!(success);
+        assert!(stdout.contains("  [ ] Try a thing due:2027-01-01 (line 3)"));
+        assert!(stdout.contains("  [x] Book room @alex (line 8)"));
+        assert!(stdout.contains("3 task(s), 2 open"), "{}", stdout);
+    }
+
+    #[test]
+    fn filters_by_status_due_owner_and_topic_fields() {
+        let temp = setup();
+        let root = temp.path().to_str().unwrap();
+        let texts = |query: &str| -> Vec<String> {
+            let (stdout, stderr, success) = run_hypha(&["--root", root, "tasks", query, "--json"]);
+            assert!(success, "stderr: {}", stderr);
+            let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+            json.as_array()
+                .unwrap()
+                .iter()
+                .map(|t| t["text"].as_str().unwrap().to_string())
+                .collect()
+        };
+
+        assert_eq!(
+            texts("status:open due<2026-12-01"),
+            vec!["Write spec due:2026-11-01 @sam"]
+        );
+        assert_eq!(texts("-status:open"), vec!["Book room @alex"]);
+        assert_eq!(texts("owner:alex"), vec!["Book room @alex"]);
+        assert_eq!(
+            texts("topic.status:active status:open"),
+            vec!["Write spec due:2026-11-01 @sam"]
+        );
+        assert_eq!(texts("thing"), vec!["Try a thing due:2027-01-01"]);
+    }
+
+    #[test]
+    fn json_includes_metadata() {
+        let temp = setup();
+
+        let (stdout, _, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "tasks",
+            "owner:sam",
+            "--json",
+        ]);
+        assert!(success);
+        let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        assert_eq!(json[0]["topic"], "Launch");
+        assert_eq!(json[0]["line"], 7);
+        assert_eq!(json[0]["done"], false);
+        assert_eq!(json[0]["due"], "2026-11-01");
+        assert_eq!(json[0]["owners"], serde_json::json!(["sam"]));
+    }
+}
+
+mod trash {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup() -> TempDir {
+        vault(&[
+            ("projects/plan.md", "# Plan\n"),
+            ("ideas.md", "# Ideas\n\n[Plan](projects/plan.md)\n"),
+        ])
+    }
+
+    #[test]
+    fn requires_confirmation_without_terminal() {
+        let temp = setup();
+        let root = temp.path().to_str().unwrap();
+
+        let (_, stderr, success) = run_hypha(&["--root", root, "delete", "plan"]);
+
+        assert!(!success);
+        assert!(stderr.contains("--yes"), "{}", stderr);
+        assert!(temp.path().join("projects/plan.md").exists());
+    }
+
+    #[test]
+    fn warns_about_inbound_links() {
+        let temp = setup();
+        let root = temp.path().to_str().unwrap();
+
+        let (_, stderr, success) = run_hypha(&["--root", root, "delete", "plan", "--yes"]);
+
+        assert!(success, "{}", stderr);
+        assert!(
+            stderr.contains("Warning: 1 topic(s) link to Plan:"),
+            "{}",
+            stderr
+        );
+        assert!(stderr.contains("ideas.md"));
+    }
+
+    #[test]
+    fn deleted_topics_can_be_restored() {
+        let temp = setup();
+        let root = temp.path().to_str().unwrap();
+        let plan = temp.path().join("projects/plan.md");
+
+        let (stdout, _, success) = run_hypha(&["--root", root, "delete", "plan", "--yes"]);
+        assert!(success);
+        assert!(
+            stdout.contains("hypha trash restore projects/plan.md"),
+            "{}",
+            stdout
+        );
+        assert!(!plan.exists());
+
+        let (stdout, _, success) = run_hypha(&["--root", root, "trash", "list"]);
+        assert!(success);
+        assert!(
+            stdout.contains("  1. projects/plan.md (deleted "),
+            "{}",
+            stdout
+        );
+
+        let (stdout, _, success) = run_hypha(&["--root", root, "trash", "list", "--json"]);
+        assert!(success);
+        let listed: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        assert_eq!(listed[0]["path"], "projects/plan.md");
+
+        let (stdout, stderr, success) = run_hypha(&["--root", root, "trash", "restore", "1"]);
+        assert!(success, "{}", stderr);
+        assert!(stdout.contains("Restored:"));
+        assert_eq!(fs::read_to_string(&plan).unwrap(), "# Plan\n");
+
+        let (stdout, _, _) = run_hypha(&["--root", root, "trash", "list"]);
+        assert!(stdout.contains("Trash is empty."));
+    }
+
+    #[test]
+    fn empties_trash() {
+        let temp = setup();
+        let root = temp.path().to_str().unwrap();
+
+        run_hypha(&["--root", root, "delete", "plan", "--yes"]);
+        run_hypha(&["--root", root, "delete", "ideas", "--yes"]);
+
+        let (_, _, success) = run_hypha(&["--root", root, "trash", "empty"]);
+        assert!(!success, "Should ask for confirmation");
+
+        let (stdout, stderr, success) = run_hypha(&["--root", root, "trash", "empty", "--yes"]);
+        assert!(success, "{}", stderr);
+        assert!(stdout.contains("Emptied trash: 2 topic(s) deleted"));
+        assert!(!temp.path().join(".hypha/trash").exists());
+    }
+}
+
+mod rename {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn renames_topic() {
+        let temp = TempDir::new().unwrap();
+
+        // Create a topic to rename
+        let original = temp.path().join("original.md");
+        fs::write(&original, "# Original Title\n\nSome content.").unwrap();
+
+        let (_, _, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "rename",
             "original",
             "renamed",
         ]);
@@ -460,6 +1690,528 @@ mod rename {
             "Renamed file should exist"
         );
     }
+
+    #[test]
+    fn updates_relative_links_and_skips_code() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("projects")).unwrap();
+        fs::write(temp.path().join("original.md"), "# Original Title\n").unwrap();
+        let plan = temp.path().join("projects/plan.md");
+        fs::write(
+            &plan,
+            "# Plan\n\n[a](../original.md#setup) [b](./../original.md) [c][ref] [[original]]\n\n```\n[x](../original.md)\n```\n\n[ref]: ../original.md\n",
+        )
+        .unwrap();
+
+        let (stdout, stderr, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "rename",
+            "original",
+            "Renamed Topic",
+        ]);
+
+        assert!(success, "{}", stderr);
+        assert!(stdout.contains("Updated 1 file(s) with new links"));
+        assert_eq!(
+            fs::read_to_string(&plan).unwrap(),
+            "# Plan\n\n[a](../renamed-topic.md#setup) [b](../renamed-topic.md) [c][ref] [[renamed-topic]]\n\n```\n[x](../original.md)\n```\n\n[ref]: ../renamed-topic.md\n"
+        );
+        assert_eq!(
+            fs::read_to_string(temp.path().join("renamed-topic.md")).unwrap(),
+            "# Renamed Topic\n"
+        );
+    }
+}
+
+mod move_topics {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup() -> TempDir {
+        vault(&[
+            (
+                "alpha.md",
+                "---\nstatus: done\n---\n\n# Alpha\n\n[Beta](beta.md) [[gamma]]\n",
+            ),
+            (
+                "beta.md",
+                "---\nstatus: done\n---\n\n# Beta\n\n[Alpha](./alpha.md#intro)\n",
+            ),
+            ("gamma.md", "# Gamma\n\n[Alpha](alpha.md)\n"),
+        ])
+    }
+
+    #[test]
+    fn moves_topic_and_updates_links() {
+        let temp = setup();
+        let root = temp.path().to_str().unwrap();
+
+        let (stdout, stderr, success) =
+            run_hypha(&["--root", root, "move", "alpha", "projects/2026"]);
+
+        assert!(success, "{}", stderr);
+        assert!(stdout.contains("Updated 2 file(s) with new links"));
+        assert!(!temp.path().join("alpha.md").exists());
+        assert_eq!(
+            fs::read_to_string(temp.path().join("projects/2026/alpha.md")).unwrap(),
+            "---\nstatus: done\n---\n\n# Alpha\n\n[Beta](../../beta.md) [[gamma]]\n"
+        );
+        assert_eq!(
+            fs::read_to_string(temp.path().join("beta.md")).unwrap(),
+            "---\nstatus: done\n---\n\n# Beta\n\n[Alpha](./projects/2026/alpha.md#intro)\n"
+        );
+        assert_eq!(
+            fs::read_to_string(temp.path().join("gamma.md")).unwrap(),
+            "# Gamma\n\n[Alpha](projects/2026/alpha.md)\n"
+        );
+    }
+
+    #[test]
+    fn bulk_moves_query_results() {
+        let temp = setup();
+        let root = temp.path().to_str().unwrap();
+
+        let (stdout, stderr, success) =
+            run_hypha(&["--root", root, "move", "--where", "status:done", "archive"]);
+
+        assert!(success, "{}", stderr);
+        assert!(stdout.contains("Updated 1 file(s) with new links"));
+        // Links between moved topics are unchanged
+        assert_eq!(
+            fs::read_to_string(temp.path().join("archive/beta.md")).unwrap(),
+            "---\nstatus: done\n---\n\n# Beta\n\n[Alpha](./alpha.md#intro)\n"
+        );
+        assert_eq!(
+            fs::read_to_string(temp.path().join("archive/alpha.md")).unwrap(),
+            "---\nstatus: done\n---\n\n# Alpha\n\n[Beta](beta.md) [[gamma]]\n"
+        );
+        assert_eq!(
+            fs::read_to_string(temp.path().join("gamma.md")).unwrap(),
+            "# Gamma\n\n[Alpha](archive/alpha.md)\n"
+        );
+
+        let (stdout, _, _) = run_hypha(&["--root", root, "lint"]);
+        assert!(!stdout.contains("Broken link"), "{}", stdout);
+    }
+
+    #[test]
+    fn rejects_destinations_outside_root() {
+        let temp = setup();
+        let root = temp.path().to_str().unwrap();
+
+        let (_, stderr, success) = run_hypha(&["--root", root, "move", "alpha", "../elsewhere"]);
+
+        assert!(!success);
+        assert!(stderr.contains("inside the root"));
+        assert!(temp.path().join("alpha.md").exists());
+    }
+
+    #[test]
+    fn rejects_destinations_in_data_dirs() {
+        let temp = setup();
+        let root = temp.path().to_str().unwrap();
+
+        for dir in [".hypha", "./.hypha/trash", ".git"] {
+            let (_, stderr, success) = run_hypha(&["--root", root, "move", "alpha", dir]);
+            assert!(!success, "{}", dir);
+            assert!(stderr.contains("Can't move topics into"), "{}", stderr);
+        }
+        assert!(temp.path().join("alpha.md").exists());
+        assert!(!temp.path().join(".hypha/alpha.md").exists());
+    }
+}
+
+mod dry_run {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup() -> TempDir {
+        vault(&[
+            ("alpha.md", "# Alpha\n\nSee [Beta](beta.md).\n"),
+            ("beta.md", "# Beta\n"),
+        ])
+    }
+
+    fn files(temp: &TempDir) -> Vec<(String, String)> {
+        let mut files: Vec<(String, String)> = fs::read_dir(temp.path())
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.extension().is_some_and(|e| e == "md"))
+            .map(|p| {
+                let name = p.file_name().unwrap().to_string_lossy().to_string();
+                (name, fs::read_to_string(&p).unwrap())
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn shows_diff_without_writing() {
+        let temp = setup();
+        let before = files(&temp);
+
+        let (stdout, stderr, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "rename",
+            "beta",
+            "Gamma",
+            "--dry-run",
+        ]);
+
+        assert!(success, "{}", stderr);
+        assert!(
+            stdout.contains("--- /dev/null\n+++ b/gamma.md\n"),
+            "{}",
+            stdout
+        );
+        assert!(
+            stdout.contains("--- a/beta.md\n+++ /dev/null\n"),
+            "{}",
+            stdout
+        );
+        assert!(
+            stdout.contains("-See [Beta](beta.md).\n+See [Beta](gamma.md).\n"),
+            "{}",
+            stdout
+        );
+        assert!(!stdout.contains("Renamed:"));
+        assert_eq!(files(&temp), before);
+    }
+
+    #[test]
+    fn lists_changes_as_json() {
+        let temp = setup();
+        let before = files(&temp);
+
+        let (stdout, stderr, success) = run_hypha(&[
+            "--root",
+            temp.path().to_str().unwrap(),
+            "--dry-run=json",
+            "set",
+            "--where",
+            "Alpha OR Beta",
+            "status=done",
+        ]);
+
+        assert!(success, "{}", stderr);
+        let changes: serde_json::Value =
+            serde_json::from_str(&stdout).expect("Should be valid JSON");
+        let changes = changes.as_array().unwrap();
+        assert_eq!(changes.len(), 2);
+        assert!(changes.iter().all(|c| c["action"] == "modify"));
+        assert!(changes[0]["diff"]
+            .as_str()
+            .unwrap()
+            .contains("+status: done\n"));
+        assert_eq!(files(&temp), before);
+    }
+
+    #[test]
+    fn new_and_delete_change_nothing() {
+        let temp = setup();
+        let root = temp.path().to_str().unwrap();
+        let before = files(&temp);
+
+        let (stdout, _, success) =
+            run_hypha(&["--root", root, "--dry-run", "new", "Delta", "--no-edit"]);
+        assert!(success);
+        assert!(stdout.contains("+++ b/delta.md"));
+
+        let (stdout, _, success) = run_hypha(&["--root", root, "--dry-run", "delete", "alpha"]);
+        assert!(success);
+        assert!(stdout.contains("--- a/alpha.md\n+++ /dev/null"));
+        assert_eq!(files(&temp), before);
+    }
+}
+
+mod undo {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn snapshot(root: &std::path::Path) -> Vec<(String, String)> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(root).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                if !path.ends_with(".hypha") {
+                    files.extend(snapshot(&path));
+                }
+            } else {
+                files.push((
+                    path.display().to_string(),
+                    fs::read_to_string(&path).unwrap(),
+                ));
+            }
+        }
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn undoes_operations_in_reverse_order() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().to_str().unwrap();
+        fs::write(temp.path().join("alpha.md"), "# Alpha\n\n[Beta](beta.md)\n").unwrap();
+        fs::write(temp.path().join("beta.md"), "# Beta\n").unwrap();
+        let original = snapshot(temp.path());
+
+        let (_, stderr, success) = run_hypha(&["--root", root, "rename", "beta", "Gamma"]);
+        assert!(success, "{}", stderr);
+        let renamed = snapshot(temp.path());
+        let (_, stderr, success) = run_hypha(&["--root", root, "move", "gamma", "archive"]);
+        assert!(success, "{}", stderr);
+        let (_, stderr, success) =
+            run_hypha(&["--root", root, "set", "--where", "Alpha", "status=done"]);
+        assert!(success, "{}", stderr);
+
+        let (stdout, stderr, success) = run_hypha(&["--root", root, "undo"]);
+        assert!(success, "{}", stderr);
+        assert!(stdout.contains("Undid: set --where Alpha status=done"));
+        let (stdout, _, _) = run_hypha(&["--root", root, "undo"]);
+        assert!(stdout.contains("Undid: move gamma archive"));
+        assert_eq!(snapshot(temp.path()), renamed);
+
+        let (stdout, _, _) = run_hypha(&["--root", root, "undo"]);
+        assert!(stdout.contains("Undid: rename beta Gamma"));
+        assert_eq!(snapshot(temp.path()), original);
+
+        let (stdout, _, success) = run_hypha(&["--root", root, "undo"]);
+        assert!(success);
+        assert!(stdout.contains("Nothing to undo."));
+    }
+
+    #[test]
+    fn refuses_to_overwrite_later_edits() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().to_str().unwrap();
+        let alpha = temp.path().join("alpha.md");
+        fs::write(&alpha, "# Alpha\n").unwrap();
+
+        let (_, _, success) = run_hypha(&["--root", root, "set", "alpha", "status=done"]);
+        assert!(success);
+        fs::write(&alpha, "# Edited\n").unwrap();
+
+        let (_, stderr, success) = run_hypha(&["--root", root, "undo"]);
+        assert!(!success);
+        assert!(stderr.contains("alpha.md has changed since"), "{}", stderr);
+        assert_eq!(fs::read_to_string(&alpha).unwrap(), "# Edited\n");
+    }
+
+    #[test]
+    fn dry_run_shows_what_undo_would_do() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().to_str().unwrap();
+        fs::write(temp.path().join("alpha.md"), "# Alpha\n").unwrap();
+
+        let (_, _, success) = run_hypha(&["--root", root, "delete", "alpha", "--yes"]);
+        assert!(success);
+
+        let (stdout, _, success) = run_hypha(&["--root", root, "undo", "--dry-run"]);
+        assert!(success);
+        assert!(
+            stdout.contains("--- /dev/null\n+++ b/alpha.md\n"),
+            "{}",
+            stdout
+        );
+        assert!(!temp.path().join("alpha.md").exists());
+
+        let (_, _, success) = run_hypha(&["--root", root, "undo"]);
+        assert!(success);
+        assert!(temp.path().join("alpha.md").exists());
+    }
+}
+
+mod graph {
+    use super::*;
+
+    fn graph_json(args: &[&str]) -> serde_json::Value {
+        let fixtures = fixtures_path();
+        let mut full = vec!["--root", fixtures.to_str().unwrap(), "graph", "-f", "json"];
+        full.extend_from_slice(args);
+        let (stdout, stderr, success) = run_hypha(&full);
+        assert!(success, "{}", stderr);
+        serde_json::from_str(&stdout).expect("Should be valid JSON")
+    }
+
+    fn ids(value: &serde_json::Value) -> Vec<&str> {
+        value["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|n| n["id"].as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn exports_whole_graph_as_json() {
+        let graph = graph_json(&[]);
+        assert_eq!(graph["nodes"].as_array().unwrap().len(), 6);
+
+        let edges: Vec<(&str, &str)> = graph["edges"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| (e["source"].as_str().unwrap(), e["target"].as_str().unwrap()))
+            .collect();
+        assert!(edges.contains(&("topic-a", "topic-b")));
+        assert!(edges.contains(&("topic-b", "topic-a")));
+        assert!(!edges.iter().any(|(s, t)| *s == "orphan" || *t == "orphan"));
+
+        let topic_a = &graph["nodes"][3];
+        assert_eq!(topic_a["title"], "Topic A");
+        assert_eq!(topic_a["fields"]["tags"][1], "example");
+    }
+
+    #[test]
+    fn exports_neighbourhood() {
+        let graph = graph_json(&["--around", "topic-c", "--depth", "1"]);
+        assert_eq!(ids(&graph), vec!["topic-a", "topic-c"]);
+
+        let graph = graph_json(&["--around", "topic-c", "--depth", "2"]);
+        assert_eq!(
+            ids(&graph),
+            vec!["broken-links", "topic-a", "topic-b", "topic-c"]
+        );
+    }
+
+    #[test]
+    fn exports_query_subgraph() {
+        let graph = graph_json(&["--where", "tags:example OR title:\"Topic B\""]);
+        assert_eq!(ids(&graph), vec!["topic-a", "topic-b"]);
+        assert_eq!(graph["edges"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn reports_stats() {
+        let fixtures = fixtures_path();
+        let (stdout, stderr, success) = run_hypha(&[
+            "--root",
+            fixtures.to_str().unwrap(),
+            "graph",
+            "stats",
+            "--json",
+            "--hub-threshold",
+            "2",
+        ]);
+        assert!(success, "{}", stderr);
+
+        let stats: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        assert_eq!(stats["topics"], 6);
+        assert_eq!(stats["orphans"], serde_json::json!(["orphan"]));
+        assert_eq!(stats["dead_ends"], serde_json::json!(["topic-c"]));
+        assert_eq!(stats["hubs"][0]["linked_from"], 2);
+        assert_eq!(stats["components"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            stats["clusters"],
+            serde_json::json!([["topic-a", "topic-b"]])
+        );
+    }
+
+    #[test]
+    fn exports_dot_and_mermaid() {
+        let fixtures = fixtures_path();
+        let root = fixtures.to_str().unwrap();
+
+        let (stdout, _, success) = run_hypha(&["--root", root, "graph"]);
+        assert!(success);
+        assert!(stdout.starts_with("digraph hypha {"));
+        assert!(stdout.contains("\"topic-a\" -> \"topic-b\";"));
+
+        let (stdout, _, success) = run_hypha(&["--root", root, "graph", "-f", "mermaid"]);
+        assert!(success);
+        assert!(stdout.starts_with("graph LR"));
+        assert!(stdout.contains("[\"Topic A\"]"));
+    }
+}
+
+mod set_fields {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup() -> TempDir {
+        vault(&[
+            (
+                "alpha.md",
+                "---\nstatus: review  # workflow\ntags:\n  - a\n---\n\n# Alpha\n",
+            ),
+            ("beta.md", "---\nstatus: review\n---\n\n# Beta\n"),
+            ("gamma.md", "# Gamma\n"),
+        ])
+    }
+
+    #[test]
+    fn sets_and_unsets_fields_on_one_topic() {
+        let temp = setup();
+        let root = temp.path().to_str().unwrap();
+
+        let (_, stderr, success) = run_hypha(&[
+            "--root",
+            root,
+            "set",
+            "alpha",
+            "status=done",
+            "due=2026-03-01",
+        ]);
+        assert!(success, "{}", stderr);
+        assert_eq!(
+            fs::read_to_string(temp.path().join("alpha.md")).unwrap(),
+            "---\nstatus: done  # workflow\ntags:\n  - a\ndue: 2026-03-01\n---\n\n# Alpha\n"
+        );
+
+        let (_, _, success) = run_hypha(&["--root", root, "unset", "alpha", "tags"]);
+        assert!(success);
+        let (stdout, _, _) = run_hypha(&["--root", root, "search", "due:2026-03-01 -tags:a"]);
+        assert!(stdout.contains("Alpha"));
+    }
+
+    #[test]
+    fn bulk_updates_query_results() {
+        let temp = setup();
+        let root = temp.path().to_str().unwrap();
+
+        let (stdout, _, success) = run_hypha(&[
+            "--root",
+            root,
+            "set",
+            "--where",
+            "status:review",
+            "status=done",
+        ]);
+        assert!(success);
+        assert!(stdout.contains("Updated 2 of 2 topic(s)"));
+
+        let (stdout, _, _) = run_hypha(&["--root", root, "search", "status:done"]);
+        assert!(stdout.contains("Alpha") && stdout.contains("Beta"));
+        assert_eq!(
+            fs::read_to_string(temp.path().join("gamma.md")).unwrap(),
+            "# Gamma\n"
+        );
+    }
+
+    #[test]
+    fn invalid_arguments_change_nothing() {
+        let temp = setup();
+        let root = temp.path().to_str().unwrap();
+        let before = fs::read_to_string(temp.path().join("beta.md")).unwrap();
+
+        let (_, stderr, success) = run_hypha(&["--root", root, "set", "beta", "status"]);
+        assert!(!success);
+        assert!(stderr.contains("Expected key=value"));
+
+        let (_, _, success) = run_hypha(&["--root", root, "set", "beta", "bad key=x"]);
+        assert!(!success);
+        assert_eq!(
+            fs::read_to_string(temp.path().join("beta.md")).unwrap(),
+            before
+        );
+    }
 }
 
 mod edge_cases {
@@ -4,15 +4,25 @@ import * as vscode from 'vscode';
 
 const execFileAsync = promisify(execFile);
 
+export interface BacklinkRef {
+    line: number;
+    column: number;
+    text: string;
+    context: string;
+}
+
 export interface Topic {
     title: string;
     description: string | null;
     path: string;
     frontmatter: Record<string, unknown>;
+    /** Links to the target topic (backlinks only). */
+    links?: BacklinkRef[];
 }
 
 export interface LintWarning {
     message: string;
+    severity?: 'error' | 'warning';
     line?: number;
     column?: number;
 }
@@ -23,6 +33,15 @@ export interface LintResult {
     warnings: LintWarning[];
 }
 
+export interface Template {
+    name: string;
+    path: string;
+    folder: string | null;
+    description: string | null;
+    /** Labels of `{prompt:...}` placeholders, answered with `--var`. */
+    prompts: string[];
+}
+
 export class HyphaCli {
     private sortOrder: 'alpha' | 'modified' | 'created' = 'alpha';
 
@@ -109,14 +128,31 @@ export class HyphaCli {
         return this.parseTopics(output);
     }
 
-    async newTopic(title: string): Promise<string> {
-        const output = await this.run(['new', title, '--no-edit']);
-        const match = output.match(/Created: (.+)/);
-        return match?.[1]?.trim() || '';
+    async newTopic(title: string, template?: string, vars: Record<string, string> = {}): Promise<string> {
+        const args = template ? ['new', '--template', template, title] : ['new', title];
+        for (const [name, value] of Object.entries(vars)) {
+            args.push('--var', `${name}=${value}`);
+        }
+        const output = await this.run([...args, '--json']);
+        return (JSON.parse(output) as { path: string }).path;
+    }
+
+    async today(): Promise<string> {
+        const output = await this.run(['today', '--print-path']);
+        return output.trim();
+    }
+
+    async templates(): Promise<Template[]> {
+        try {
+            const output = await this.run(['templates', 'list', '--json']);
+            return JSON.parse(output) as Template[];
+        } catch {
+            return [];
+        }
     }
 
-    async deleteTopic(topic: string): Promise<void> {
-        await this.run(['delete', topic]);
+    async deleteTopic(path: string): Promise<void> {
+        await this.run(['delete', path, '--yes']);
     }
 
     async renameTopic(oldName: string, newName: string): Promise<void> {
@@ -154,6 +190,7 @@ export class HyphaCli {
                 description: t.description || null,
                 path: t.path,
                 frontmatter: t.frontmatter || {},
+                links: t.links,
             }));
         } catch {
             return [];
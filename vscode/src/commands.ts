@@ -1,5 +1,5 @@
 import * as vscode from 'vscode';
-import { HyphaCli } from './cli';
+import { HyphaCli, Template } from './cli';
 import { TopicTreeProvider, TopicItem } from './topicTree';
 import { DEBOUNCE_MS, PROJECT_REPO_URL, MAX_RECENT_TOPICS, MAX_DESCRIPTION_LENGTH, SEPARATOR_WIDTH } from './constants';
 
@@ -10,14 +10,39 @@ export function registerCommands(
 ): void {
     context.subscriptions.push(
         vscode.commands.registerCommand('hypha.new', async () => {
+            let template: Template | undefined;
+            const templates = await cli.templates();
+            if (templates.length > 0) {
+                const selected = await vscode.window.showQuickPick(
+                    [
+                        { label: 'Default', description: '', template: undefined },
+                        ...templates.map(t => ({
+                            label: t.name,
+                            description: t.description || '',
+                            template: t as Template | undefined,
+                        })),
+                    ],
+                    { placeHolder: 'Select template' }
+                );
+                if (!selected) return;
+                template = selected.template;
+            }
+
             const title = await vscode.window.showInputBox({
                 prompt: 'Enter topic title',
                 placeHolder: 'My New Topic',
             });
             if (!title) return;
 
+            const vars: Record<string, string> = {};
+            for (const label of template?.prompts ?? []) {
+                const value = await vscode.window.showInputBox({ prompt: label });
+                if (value === undefined) return;
+                vars[label.toLowerCase()] = value;
+            }
+
             try {
-                const filePath = await cli.newTopic(title);
+                const filePath = await cli.newTopic(title, template?.name, vars);
                 treeProvider.refresh();
 
                 if (filePath) {
@@ -30,6 +55,20 @@ export function registerCommands(
         })
     );
 
+    context.subscriptions.push(
+        vscode.commands.registerCommand('hypha.today', async () => {
+            try {
+                const filePath = await cli.today();
+                treeProvider.refresh();
+
+                const doc = await vscode.workspace.openTextDocument(filePath);
+                await vscode.window.showTextDocument(doc);
+            } catch (err) {
+                vscode.window.showErrorMessage(`Failed to open journal note: ${err}`);
+            }
+        })
+    );
+
     context.subscriptions.push(
         vscode.commands.registerCommand('hypha.openTopic', async (item?: TopicItem) => {
             if (!item) {
@@ -98,7 +137,7 @@ export function registerCommands(
             if (confirm !== 'Delete') return;
 
             try {
-                await cli.deleteTopic(item.topic.title);
+                await cli.deleteTopic(item.topic.path);
                 treeProvider.refresh();
             } catch (err) {
                 vscode.window.showErrorMessage(`Failed to delete topic: ${err}`);
@@ -47,7 +47,9 @@ export async function activate(context: vscode.ExtensionContext): Promise<void>
                     const diagnostic = new vscode.Diagnostic(
                         new vscode.Range(line, column, line, column),
                         warning.message,
-                        vscode.DiagnosticSeverity.Warning
+                        warning.severity === 'error'
+                            ? vscode.DiagnosticSeverity.Error
+                            : vscode.DiagnosticSeverity.Warning
                     );
                     diagnostic.source = 'hypha';
                     return diagnostic;
@@ -92,6 +92,16 @@ export class TopicItem extends vscode.TreeItem {
             this.tooltip.appendMarkdown(`${topic.description}\n\n`);
         }
         
+        if (topic.links && topic.links.length > 0) {
+            this.description = `${topic.links.length} link${topic.links.length === 1 ? '' : 's'}`;
+            this.tooltip.appendMarkdown(`---\n\n`);
+            for (const link of topic.links) {
+                this.tooltip.appendMarkdown(`**Line ${link.line}:** `);
+                this.tooltip.appendText(link.context || link.text);
+                this.tooltip.appendMarkdown(`\n\n`);
+            }
+        }
+
         if (Object.keys(topic.frontmatter || {}).length > 0) {
             this.tooltip.appendMarkdown(`---\n\n`);
             for (const [key, value] of Object.entries(topic.frontmatter || {})) {